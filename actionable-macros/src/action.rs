@@ -1,6 +1,9 @@
 #![allow(clippy::default_trait_access)]
 
+use std::{collections::HashSet, str::FromStr};
+
 use darling::{ast, FromDeriveInput, FromField, FromVariant, ToTokens};
+use ident_case::RenameRule;
 use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use quote::quote;
@@ -20,13 +23,300 @@ struct Action {
 }
 
 #[derive(Debug, FromVariant)]
+#[darling(attributes(action), forward_attrs(doc))]
 struct Variant {
     ident: syn::Ident,
     fields: ast::Fields<Field>,
+    attrs: Vec<syn::Attribute>,
+
+    /// The names of sibling variants that are allowed when this variant is
+    /// allowed. `*` implies all sibling variants.
+    #[darling(default, multiple)]
+    implies: Vec<String>,
+
+    /// Overrides the name of this variant's segment of the action name.
+    #[darling(default)]
+    rename: Option<String>,
+
+    /// This variant's segment of the action name, after renaming.
+    #[darling(skip)]
+    name: String,
+
+    /// This variant's doc comment.
+    #[darling(skip)]
+    description: Option<String>,
 }
 
 #[derive(Debug, FromField)]
-struct Field {}
+#[darling(attributes(action))]
+struct Field {
+    ident: Option<syn::Ident>,
+    ty: syn::Type,
+
+    /// Excludes this field from the action name. Skipped fields are
+    /// initialized using `Default` when parsing an action name.
+    #[darling(default)]
+    skip: bool,
+}
+
+impl Variant {
+    /// Returns the field containing this variant's subaction, if any.
+    fn subaction(&self) -> Option<&Field> {
+        let mut fields = self.fields.iter().filter(|field| !field.skip);
+        let subaction = fields.next();
+        if fields.next().is_some() {
+            abort!(
+                self.ident,
+                "For derive(Action), all enum variants may have at most 1 field that isn't marked \
+                 `#[action(skip)]`"
+            )
+        }
+        subaction
+    }
+
+    /// Returns a pattern matching this variant, binding the subaction to
+    /// `subaction`.
+    fn pattern(&self) -> TokenStream {
+        let ident = &self.ident;
+        match self.fields.style {
+            ast::Style::Unit => quote!(Self::#ident),
+            ast::Style::Tuple => {
+                let fields = self.fields.iter().map(|field| {
+                    if field.skip {
+                        quote!(_)
+                    } else {
+                        quote!(subaction)
+                    }
+                });
+                quote!(Self::#ident(#(#fields),*))
+            }
+            ast::Style::Struct => {
+                if let Some(field) = self.subaction().and_then(|field| field.ident.as_ref()) {
+                    quote!(Self::#ident { #field: subaction, .. })
+                } else {
+                    quote!(Self::#ident { .. })
+                }
+            }
+        }
+    }
+
+    /// Returns an expression constructing this variant from `subaction`.
+    fn constructor(&self) -> TokenStream {
+        let ident = &self.ident;
+        let values = self.fields.iter().map(|field| {
+            let value = if field.skip {
+                quote!(::std::default::Default::default())
+            } else {
+                quote!(subaction)
+            };
+            match &field.ident {
+                Some(field) => quote!(#field: #value),
+                None => value,
+            }
+        });
+        match self.fields.style {
+            ast::Style::Unit => quote!(Self::#ident),
+            ast::Style::Tuple => quote!(Self::#ident(#(#values),*)),
+            ast::Style::Struct => quote!(Self::#ident { #(#values),* }),
+        }
+    }
+
+    /// Returns the match arm for `Action::name()`.
+    fn name_arm(&self, actionable: &syn::Path) -> TokenStream {
+        let pattern = self.pattern();
+        let name = &self.name;
+        if self.subaction().is_some() {
+            quote! {
+                #pattern => {
+                    let mut name = Action::name(subaction);
+                    name.0.insert(0, ::std::borrow::Cow::Borrowed(#name));
+                    name
+                }
+            }
+        } else {
+            quote! {
+                #pattern => #actionable::ActionName(vec![::std::borrow::Cow::Borrowed(#name)])
+            }
+        }
+    }
+
+    /// Returns the match arm for `Action::implied_by()`, where `implied_by`
+    /// contains the names of the sibling variants that imply this variant.
+    fn implied_by_arm(&self, implied_by: &[String], actionable: &syn::Path) -> TokenStream {
+        let pattern = self.pattern();
+        let implied_by = implied_by.iter().map(|name| {
+            quote! {
+                #actionable::ActionName(vec![::std::borrow::Cow::Borrowed(#name)])
+            }
+        });
+        if self.subaction().is_some() {
+            let name = &self.name;
+            quote! {
+                #pattern => {
+                    let mut names = Action::implied_by(subaction);
+                    for implied_by in &mut names {
+                        implied_by.0.insert(0, ::std::borrow::Cow::Borrowed(#name));
+                    }
+                    names.extend(vec![#(#implied_by),*]);
+                    names
+                }
+            }
+        } else {
+            quote! {
+                #pattern => vec![#(#implied_by),*]
+            }
+        }
+    }
+
+    /// Returns the match arm for `Action::description()`.
+    fn description_arm(&self) -> TokenStream {
+        let pattern = self.pattern();
+        let description = self.description_tokens();
+        if self.subaction().is_some() {
+            quote! {
+                #pattern => Action::description(subaction).or(#description)
+            }
+        } else {
+            quote! {
+                #pattern => #description
+            }
+        }
+    }
+
+    /// Returns the match arm for `Action::description_of()`, which matches
+    /// the first segment of `name`.
+    fn description_of_arm(&self, actionable: &syn::Path) -> TokenStream {
+        let segment = &self.name;
+        let description = self.description_tokens();
+        if let Some(field) = self.subaction() {
+            let ty = &field.ty;
+            quote! {
+                Some(#segment) if name.0.len() == 1 => #description,
+                Some(#segment) => {
+                    let subaction = #actionable::ActionName(name.0[1..].to_vec());
+                    <#ty as Action>::description_of(&subaction).or(#description)
+                }
+            }
+        } else {
+            quote! {
+                Some(#segment) if name.0.len() == 1 => #description
+            }
+        }
+    }
+
+    fn description_tokens(&self) -> TokenStream {
+        if let Some(description) = &self.description {
+            quote!(Some(#description))
+        } else {
+            quote!(None)
+        }
+    }
+
+    /// Returns the match arm for `TryFrom<&ActionName>`, which matches the
+    /// first segment of `name`.
+    fn parse_arm(&self, actionable: &syn::Path) -> TokenStream {
+        let segment = &self.name;
+        let constructor = self.constructor();
+        if let Some(field) = self.subaction() {
+            let ty = &field.ty;
+            quote! {
+                Some(#segment) => {
+                    let subaction = #actionable::ActionName(name.0[1..].to_vec());
+                    <#ty as ::std::convert::TryFrom<&#actionable::ActionName>>::try_from(&subaction)
+                        .map(|subaction| #constructor)
+                        .map_err(|err| err.within(name, 1))
+                }
+            }
+        } else {
+            quote! {
+                Some(#segment) => match name.0.get(1) {
+                    None => Ok(#constructor),
+                    Some(segment) => Err(#actionable::ActionNameError::UnexpectedSegment {
+                        name: name.to_string(),
+                        segment: segment.to_string(),
+                        position: 1,
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Returns the statements that push this variant's names onto `names`
+    /// in `Action::all_action_names()`.
+    fn push_action_names(&self, actionable: &syn::Path) -> TokenStream {
+        let segment = &self.name;
+        if let Some(field) = self.subaction() {
+            let ty = &field.ty;
+            quote! {
+                names.extend(<#ty as Action>::all_action_names().into_iter().map(|mut name| {
+                    name.0.insert(0, ::std::borrow::Cow::Borrowed(#segment));
+                    name
+                }));
+            }
+        } else {
+            quote! {
+                names.push(#actionable::ActionName(vec![::std::borrow::Cow::Borrowed(#segment)]));
+            }
+        }
+    }
+}
+
+impl Action {
+    /// Returns the `TryFrom<&ActionName>` and `FromStr` implementations.
+    fn parse_impls(&self, enum_data: &[&Variant], actionable: &syn::Path) -> TokenStream {
+        let name = &self.ident;
+        let (_, type_generics, _) = self.generics.split_for_impl();
+        let parse_arms = enum_data
+            .iter()
+            .map(|variant| variant.parse_arm(actionable));
+        // Generic subactions must also be parseable, and generic skipped
+        // fields must implement Default.
+        let mut parse_generics = self.generics.clone();
+        if !parse_generics.params.is_empty() {
+            let where_clause = parse_generics.make_where_clause();
+            for field in enum_data.iter().flat_map(|variant| variant.fields.iter()) {
+                let ty = &field.ty;
+                where_clause.predicates.push(if field.skip {
+                    syn::parse_quote!(#ty: ::std::default::Default)
+                } else {
+                    syn::parse_quote! {
+                        #ty: for<'__name> ::std::convert::TryFrom<&'__name #actionable::ActionName, Error = #actionable::ActionNameError>
+                    }
+                });
+            }
+        }
+        let (parse_impl_generics, _, parse_where_clause) = parse_generics.split_for_impl();
+
+        quote! {
+            impl#parse_impl_generics ::std::convert::TryFrom<&#actionable::ActionName> for #name#type_generics #parse_where_clause {
+                type Error = #actionable::ActionNameError;
+
+                fn try_from(name: &#actionable::ActionName) -> Result<Self, Self::Error> {
+                    match name.0.first().map(|segment| segment.as_ref()) {
+                        #(#parse_arms,)*
+                        Some(segment) => Err(#actionable::ActionNameError::UnknownSegment {
+                            name: name.to_string(),
+                            segment: segment.to_string(),
+                            position: 0,
+                        }),
+                        None => Err(#actionable::ActionNameError::Incomplete {
+                            name: name.to_string(),
+                        }),
+                    }
+                }
+            }
+
+            impl#parse_impl_generics ::std::str::FromStr for #name#type_generics #parse_where_clause {
+                type Err = #actionable::ActionNameError;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    let name = s.parse::<#actionable::ActionName>()?;
+                    <Self as ::std::convert::TryFrom<&#actionable::ActionName>>::try_from(&name)
+                }
+            }
+        }
+    }
+}
 
 impl ToTokens for Action {
     fn to_tokens(&self, tokens: &mut TokenStream) {
@@ -54,35 +344,32 @@ impl ToTokens for Action {
             });
         let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
 
-        let variants = enum_data.into_iter().map(|variant| {
-			let ident = variant.ident.clone();
-			let ident_as_string = ident.to_string();
-			match variant.fields.len() {
-				0 => {
-					quote! {
-						Self::#ident => #actionable::ActionName(vec![::std::borrow::Cow::Borrowed(#ident_as_string)])
-					}
-				}
-				1 => {
-					quote! {
-						Self::#ident(subaction) => {
-							let mut name = Action::name(subaction);
-							name.0.insert(0, ::std::borrow::Cow::Borrowed(#ident_as_string));
-							name
-						}
-					}
-				}
-				_ => {
-					abort!(
-						variant.ident,
-						"For derive(Action), all enum variants may have at most 1 field"
-					)
-				}
-			}
-		});
+        let variants = enum_data
+            .iter()
+            .map(|variant| variant.name_arm(&actionable));
+        let implied_by = enum_data
+            .iter()
+            .zip(implications(&enum_data))
+            .map(|(variant, implied_by)| variant.implied_by_arm(&implied_by, &actionable));
+        let all_action_names = enum_data
+            .iter()
+            .map(|variant| variant.push_action_names(&actionable));
+        let descriptions = enum_data.iter().map(|variant| variant.description_arm());
+        let descriptions_of = enum_data
+            .iter()
+            .map(|variant| variant.description_of_arm(&actionable));
 
+        if matches!(&self.actionable, Some(args) if args.parse) {
+            tokens.extend(self.parse_impls(&enum_data, &actionable));
+        }
         tokens.extend(quote! {
             impl#impl_generics Action for #name#type_generics #where_clause {
+                fn all_action_names() -> Vec<#actionable::ActionName> {
+                    let mut names = Vec::new();
+                    #(#all_action_names)*
+                    names
+                }
+
                 fn name(&self) -> #actionable::ActionName {
                     match self {
                         #(
@@ -90,11 +377,115 @@ impl ToTokens for Action {
                         ),*
                     }
                 }
+
+                fn implied_by(&self) -> Vec<#actionable::ActionName> {
+                    match self {
+                        #(
+                            #implied_by
+                        ),*
+                    }
+                }
+
+                fn description(&self) -> Option<&'static str> {
+                    match self {
+                        #(
+                            #descriptions
+                        ),*
+                    }
+                }
+
+                fn description_of(name: &#actionable::ActionName) -> Option<&'static str> {
+                    match name.0.first().map(|segment| segment.as_ref()) {
+                        #(#descriptions_of,)*
+                        _ => None,
+                    }
+                }
             }
         });
     }
 }
 
+/// Returns the action names of the variants that imply each variant, following
+/// implications transitively.
+fn implications(variants: &[&Variant]) -> Vec<Vec<String>> {
+    let names = variants
+        .iter()
+        .map(|variant| variant.ident.to_string())
+        .collect::<Vec<_>>();
+    // The variants directly implied by each variant.
+    let implies = variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+            let mut implied = HashSet::new();
+            for name in &variant.implies {
+                if name == "*" {
+                    implied.extend((0..names.len()).filter(|other| *other != index));
+                } else if let Some(implied_index) = names.iter().position(|other| other == name) {
+                    implied.insert(implied_index);
+                } else {
+                    abort!(
+                        variant.ident,
+                        "`implies` must name a variant of this enum or `*`, found `{}`",
+                        name
+                    )
+                }
+            }
+            implied
+        })
+        .collect::<Vec<_>>();
+
+    (0..variants.len())
+        .map(|implied_index| {
+            names
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| {
+                    *index != implied_index && reaches(&implies, *index, implied_index)
+                })
+                .map(|(index, _)| variants[index].name.clone())
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the text of the doc comment in `attrs`, if present.
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(line),
+                ..
+            })) => Some(line.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+        .collect::<Vec<_>>();
+    let description = lines.join("\n").trim().to_string();
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
+}
+
+/// Returns true if `target` is implied by `from`, directly or indirectly.
+fn reaches(implies: &[HashSet<usize>], from: usize, target: usize) -> bool {
+    let mut visited = HashSet::new();
+    let mut pending = vec![from];
+    while let Some(index) = pending.pop() {
+        for implied in &implies[index] {
+            if *implied == target {
+                return true;
+            } else if visited.insert(*implied) {
+                pending.push(*implied);
+            }
+        }
+    }
+    false
+}
+
 pub fn derive(input: &syn::DeriveInput) -> Result<TokenStream, darling::Error> {
     let mut actionable = Action::from_derive_input(input)?;
 
@@ -107,5 +498,47 @@ pub fn derive(input: &syn::DeriveInput) -> Result<TokenStream, darling::Error> {
         actionable.actionable = Some(args);
     }
 
+    let rename_all = match actionable
+        .actionable
+        .as_ref()
+        .and_then(|args| args.rename_all.as_ref())
+    {
+        Some(rule) => RenameRule::from_str(&rule.value()).unwrap_or_else(|()| {
+            abort!(
+                rule,
+                "unknown `rename_all` rule, expected one of `lowercase`, `PascalCase`, \
+                 `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE` or `kebab-case`"
+            )
+        }),
+        None => RenameRule::None,
+    };
+    if let ast::Data::Enum(variants) = &mut actionable.data {
+        let mut names = HashSet::new();
+        for variant in variants {
+            variant.description = doc_comment(&variant.attrs);
+            variant.name = variant
+                .rename
+                .clone()
+                .unwrap_or_else(|| rename_all.apply_to_variant(variant.ident.to_string()));
+            // Action names are written with `.` between segments, and `*`
+            // matches any segment, so neither can be used within a segment.
+            if variant.name.is_empty() || variant.name.contains('.') || variant.name == "*" {
+                abort!(
+                    variant.ident,
+                    "`{}` can't be used as an action name, names must not be empty, contain `.` \
+                     or be `*`",
+                    variant.name
+                );
+            }
+            if !names.insert(variant.name.clone()) {
+                abort!(
+                    variant.ident,
+                    "another variant already uses the action name `{}`",
+                    variant.name
+                );
+            }
+        }
+    }
+
     Ok(actionable.into_token_stream())
 }
@@ -184,6 +184,11 @@ impl Variant {
                     type Action: #actionable::Action;
                     fn action() -> Self::Action;
 
+                    #[allow(clippy::ptr_arg, clippy::too_many_arguments, unused_variables)]
+                    fn context<'a>(&'a self,#(#byref_method_parameters),*) -> Result<#actionable::Context, #self_as_dispatcher::Error> {
+                        Ok(#actionable::Context::default())
+                    }
+
                     #[allow(clippy::too_many_arguments)]
                     #async_keyword fn handle(
                         &self,
@@ -192,7 +197,8 @@ impl Variant {
                     ) -> #result_type {
                         let resource = self.resource_name(#(&#enum_parameters),*)#await_suffix?;
                         let action = Self::action();
-                        permissions.check(&resource, &action)?;
+                        let context = self.context(#(&#enum_parameters),*)?;
+                        permissions.check_with(&resource, &action, &context)?;
                         self.handle_protected(permissions, #(#enum_parameters),*)#await_suffix
                     }
 
@@ -393,6 +399,12 @@ pub fn derive(input: &syn::DeriveInput) -> Result<TokenStream, darling::Error> {
         .find(|attr| attr.path.segments.first().unwrap().ident == "actionable")
     {
         let args: ActionableArgs = syn::parse2(attr.tokens.clone())?;
+        if let Some(rename_all) = &args.rename_all {
+            abort!(
+                rename_all,
+                "`rename_all` is only supported by derive(Action)"
+            );
+        }
         actionable.actionable = Some(args);
     }
 
@@ -20,11 +20,39 @@ mod dispatcher;
 
 /// Derives the `actionable::Action` trait.
 ///
+/// Along with `Action::name()`, this implements `Action::all_action_names()`,
+/// which returns the name of every unit variant and every name produced by
+/// each subaction variant. `Action::description()` and
+/// `Action::description_of()` return each variant's doc comment.
+///
 /// This trait can be customizd using the `action` attribute in these ways:
 ///
 /// * Crate name override: `#[action(actionable = "someothername")]`. If you
 ///   find yourself needing to import `actionable` as another name, this setting
 ///   will replace all mentions of `actionable` with the identifier specified.
+/// * Parsing: `#[action(parse)]` on the enum also implements
+///   `TryFrom<&ActionName>` and `FromStr`, converting names such as `Post.Read`
+///   back into the enum. The type of every subaction must also implement
+///   `TryFrom<&ActionName, Error = ActionNameError>`, such as by deriving
+///   `Action` with `#[action(parse)]`.
+/// * Implied actions: `#[action(implies = "Read")]` on a variant. When the
+///   variant is allowed, the named sibling variant is also allowed, unless it
+///   is explicitly denied. `#[action(implies = "*")]` implies every sibling
+///   variant. Multiple `implies` may be specified, and implications are
+///   followed transitively. Variants are referred to by their Rust names,
+///   regardless of any renaming.
+/// * Renaming a variant: `#[action(rename = "delete")]` on a variant uses
+///   `delete` as the variant's segment of the action name instead of the
+///   variant's name. The name must not be empty, contain `.` or be `*`.
+/// * Renaming all variants: `#[action(rename_all = "snake_case")]` on the enum
+///   converts each variant's name using the same rules as serde: `lowercase`,
+///   `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE` or
+///   `kebab-case`. `rename` on a variant takes priority.
+/// * Skipping a field: `#[action(skip)]` on a field excludes it from the action
+///   name. A variant may have at most one field that isn't skipped, which is
+///   the variant's subaction. When parsing, skipped fields are initialized
+///   using `Default::default()`, so the types of skipped fields must implement
+///   `Default`.
 #[proc_macro_error]
 #[proc_macro_derive(Action, attributes(action))]
 pub fn action_derive(input: TokenStream) -> TokenStream {
@@ -286,6 +314,8 @@ pub(crate) enum Error {
 enum ActionableArg {
     Actionable(syn::Path),
     Async,
+    Parse,
+    RenameAll(syn::LitStr),
 }
 
 impl Parse for ActionableArg {
@@ -296,8 +326,16 @@ impl Parse for ActionableArg {
             Ok(Self::Actionable(input.parse()?))
         } else if ident == "async" {
             Ok(Self::Async)
+        } else if ident == "parse" {
+            Ok(Self::Parse)
+        } else if ident == "rename_all" {
+            let _: syn::Token![=] = input.parse()?;
+            Ok(Self::RenameAll(input.parse()?))
         } else {
-            abort!(ident, "expected `actionable` or `async`")
+            abort!(
+                ident,
+                "expected `actionable`, `async`, `parse` or `rename_all`"
+            )
         }
     }
 }
@@ -306,6 +344,8 @@ impl Parse for ActionableArg {
 struct ActionableArgs {
     actionable: Option<syn::Path>,
     asynchronous: bool,
+    parse: bool,
+    rename_all: Option<syn::LitStr>,
 }
 
 impl Parse for ActionableArgs {
@@ -320,6 +360,8 @@ impl Parse for ActionableArgs {
             match arg {
                 ActionableArg::Actionable(path) => result.actionable = Some(path),
                 ActionableArg::Async => result.asynchronous = true,
+                ActionableArg::Parse => result.parse = true,
+                ActionableArg::RenameAll(rule) => result.rename_all = Some(rule),
             }
         }
 
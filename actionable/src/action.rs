@@ -1,14 +1,57 @@
 use std::{
     borrow::Cow,
+    collections::HashMap,
     fmt::{Display, Write},
+    str::FromStr,
 };
 
 use serde::{Deserialize, Serialize};
 
+use crate::{ActionNameList, Statement};
+
 /// An action that can be allowed or disallowed.
 pub trait Action: Send + Sync {
     /// The full name of this action.
     fn name(&self) -> ActionName;
+
+    /// Returns the full names of other actions that also allow this action
+    /// when they are allowed. For example, allowing `Post.Update` could also
+    /// allow `Post.Read`, in which case `Post.Read` would return
+    /// `[Post.Update]`.
+    ///
+    /// When deriving `Action`, implications can be declared using
+    /// `#[action(implies = "VariantName")]` on the implying variant.
+    fn implied_by(&self) -> Vec<ActionName> {
+        Vec::new()
+    }
+
+    /// Returns the full names of every action this type can produce. When
+    /// deriving `Action`, this includes the names of all subactions.
+    #[must_use]
+    fn all_action_names() -> Vec<ActionName>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+
+    /// Returns a human-readable description of this action. When deriving
+    /// `Action`, this is the variant's doc comment. Subaction variants return
+    /// the subaction's description, falling back to their own.
+    fn description(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the [description](Self::description) of the action named
+    /// `name`, if this type produces an action with that name.
+    #[must_use]
+    #[allow(unused_variables)]
+    fn description_of(name: &ActionName) -> Option<&'static str>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 impl Action for () {
@@ -24,10 +67,59 @@ impl Action for ActionName {
 }
 
 /// A unique name of an action.
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+///
+/// When used in a [`Statement`](crate::Statement), a segment equal to
+/// [`ActionName::WILDCARD`] matches any single segment. For example, `*.Read`
+/// matches `Post.Read` and `User.Read`, but not `Post.Update` or `Read`.
+#[derive(Default, Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[allow(clippy::module_name_repetitions)] // exported without the module name
 pub struct ActionName(pub Vec<Cow<'static, str>>);
 
+impl ActionName {
+    /// A segment that matches any single segment when used in a
+    /// [`Statement`](crate::Statement).
+    pub const WILDCARD: &'static str = "*";
+}
+
+impl ActionName {
+    /// Returns true if this name is equal to or a prefix of `other`, treating
+    /// [wildcard](Self::WILDCARD) segments in this name as matching any
+    /// segment.
+    pub(crate) fn is_prefix_of(&self, other: &Self) -> bool {
+        self.0.len() <= other.0.len()
+            && self
+                .0
+                .iter()
+                .zip(&other.0)
+                .all(|(ours, theirs)| ours == Self::WILDCARD || ours == theirs)
+    }
+}
+
+impl FromStr for ActionName {
+    type Err = ActionNameError;
+
+    /// Parses a name whose segments are separated by `.`, such as
+    /// `Post.Read`. An empty string parses as an empty name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+
+        s.split('.')
+            .map(|segment| {
+                if segment.is_empty() {
+                    Err(ActionNameError::EmptySegment {
+                        name: s.to_string(),
+                    })
+                } else {
+                    Ok(Cow::Owned(segment.to_string()))
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
 impl Display for ActionName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for (index, name) in self.0.iter().enumerate() {
@@ -41,4 +133,148 @@ impl Display for ActionName {
     }
 }
 
+/// A collection of known action names, gathered from one or more [`Action`]
+/// implementations. This can be used to list the actions available to
+/// policy authors, or to validate statements against the known actions.
+#[derive(Default, Debug, Clone)]
+pub struct ActionRegistry {
+    names: Vec<ActionName>,
+    descriptions: HashMap<ActionName, &'static str>,
+}
+
+impl ActionRegistry {
+    /// Adds every name returned by [`Action::all_action_names()`] for `A`,
+    /// along with their [descriptions](Action::description_of).
+    pub fn register<A: Action>(&mut self) {
+        for name in A::all_action_names() {
+            if let Some(description) = A::description_of(&name) {
+                self.descriptions.insert(name.clone(), description);
+            }
+            self.names.push(name);
+        }
+        self.names.sort_by(|a, b| a.0.cmp(&b.0));
+        self.names.dedup();
+    }
+
+    /// Adds every name returned by [`Action::all_action_names()`] for `A` and
+    /// returns self.
+    #[must_use]
+    pub fn with<A: Action>(mut self) -> Self {
+        self.register::<A>();
+        self
+    }
+
+    /// Returns the sorted list of registered action names.
+    #[must_use]
+    pub fn names(&self) -> &[ActionName] {
+        &self.names
+    }
+
+    /// Returns the description of the registered action named `name`, if it
+    /// has one.
+    #[must_use]
+    pub fn description(&self, name: &ActionName) -> Option<&'static str> {
+        self.descriptions.get(name).copied()
+    }
+
+    /// Returns true if `name` matches at least one registered action. `name`
+    /// may be a prefix of a registered action, such as `Post` for
+    /// `Post.Read`, and may contain [wildcard](ActionName::WILDCARD)
+    /// segments.
+    #[must_use]
+    pub fn matches(&self, name: &ActionName) -> bool {
+        self.names
+            .iter()
+            .any(|registered| name.is_prefix_of(registered))
+    }
+
+    /// Returns the action names in `statements` that don't
+    /// [match](Self::matches) any registered action.
+    #[must_use]
+    pub fn unknown_actions<'a>(
+        &self,
+        statements: impl IntoIterator<Item = &'a Statement>,
+    ) -> Vec<ActionName> {
+        statements
+            .into_iter()
+            .filter_map(|statement| match &statement.actions {
+                Some(ActionNameList::List(names)) => Some(names),
+                _ => None,
+            })
+            .flatten()
+            .filter(|name| !self.matches(name))
+            .cloned()
+            .collect()
+    }
+}
+
+/// An error converting an [`ActionName`] or string into an [`Action`].
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+#[allow(clippy::module_name_repetitions)] // exported without the module name
+pub enum ActionNameError {
+    /// The name contains an empty segment, such as `Post..Read`.
+    #[error("action name '{name}' contains an empty segment")]
+    EmptySegment {
+        /// The name being parsed.
+        name: String,
+    },
+    /// A segment of the name doesn't match any action.
+    #[error("unknown action '{segment}' at position {position} in '{name}'")]
+    UnknownSegment {
+        /// The name being parsed.
+        name: String,
+        /// The unknown segment.
+        segment: String,
+        /// The index of the unknown segment.
+        position: usize,
+    },
+    /// The name ended before reaching an action. For example, `Post` when
+    /// `Post` has subactions.
+    #[error("action name '{name}' is incomplete")]
+    Incomplete {
+        /// The name being parsed.
+        name: String,
+    },
+    /// The name continues after reaching an action. For example,
+    /// `Post.Read.Draft` when `Post.Read` has no subactions.
+    #[error("unexpected '{segment}' at position {position} in '{name}'")]
+    UnexpectedSegment {
+        /// The name being parsed.
+        name: String,
+        /// The unexpected segment.
+        segment: String,
+        /// The index of the unexpected segment.
+        position: usize,
+    },
+}
+
+impl ActionNameError {
+    /// Returns this error, which occurred parsing the segments of `name`
+    /// after the first `offset` segments, adjusted to describe the entirety
+    /// of `name`. This is used by `derive(Action)` when parsing subactions.
+    #[doc(hidden)]
+    #[must_use]
+    pub fn within(self, name: &ActionName, offset: usize) -> Self {
+        let name = name.to_string();
+        match self {
+            Self::EmptySegment { .. } => Self::EmptySegment { name },
+            Self::Incomplete { .. } => Self::Incomplete { name },
+            Self::UnknownSegment {
+                segment, position, ..
+            } => Self::UnknownSegment {
+                name,
+                segment,
+                position: position + offset,
+            },
+            Self::UnexpectedSegment {
+                segment, position, ..
+            } => Self::UnexpectedSegment {
+                name,
+                segment,
+                position: position + offset,
+            },
+        }
+    }
+}
+
 pub use actionable_macros::Action;
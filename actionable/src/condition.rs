@@ -0,0 +1,253 @@
+use std::{cmp::Ordering, collections::HashMap, time::SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Configuration;
+
+/// A condition that must be met for a [`Statement`](crate::Statement) to
+/// apply. Conditions are evaluated against a [`Context`], which contains
+/// values describing the request being made, such as the time of day or the
+/// address the request was made from.
+///
+/// Comparisons are performed numerically if either value is an integer and
+/// both values can be interpreted as an integer. Otherwise, string values are
+/// compared lexicographically. If the key is not present in the [`Context`],
+/// or the values can't be compared, the comparison evaluates to false.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    /// The value of the key is equal to the value.
+    Equals(String, Configuration),
+    /// The value of the key is present and not equal to the value.
+    NotEquals(String, Configuration),
+    /// The value of the key is less than the value.
+    LessThan(String, Configuration),
+    /// The value of the key is less than or equal to the value.
+    LessThanOrEqual(String, Configuration),
+    /// The value of the key is greater than the value.
+    GreaterThan(String, Configuration),
+    /// The value of the key is greater than or equal to the value.
+    GreaterThanOrEqual(String, Configuration),
+    /// The value of the key is a string that starts with the value.
+    StartsWith(String, String),
+    /// The key is present.
+    Exists(String),
+    /// All of the conditions are met.
+    All(Vec<Condition>),
+    /// At least one of the conditions is met.
+    Any(Vec<Condition>),
+    /// The condition is not met.
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// Returns a condition that is met when `key` is equal to `value`.
+    pub fn equals<K: Into<String>, V: Into<Configuration>>(key: K, value: V) -> Self {
+        Self::Equals(key.into(), value.into())
+    }
+
+    /// Returns a condition that is met when `key` is present and not equal to
+    /// `value`.
+    pub fn not_equals<K: Into<String>, V: Into<Configuration>>(key: K, value: V) -> Self {
+        Self::NotEquals(key.into(), value.into())
+    }
+
+    /// Returns a condition that is met when `key` is less than `value`.
+    pub fn less_than<K: Into<String>, V: Into<Configuration>>(key: K, value: V) -> Self {
+        Self::LessThan(key.into(), value.into())
+    }
+
+    /// Returns a condition that is met when `key` is less than or equal to
+    /// `value`.
+    pub fn less_than_or_equal<K: Into<String>, V: Into<Configuration>>(key: K, value: V) -> Self {
+        Self::LessThanOrEqual(key.into(), value.into())
+    }
+
+    /// Returns a condition that is met when `key` is greater than `value`.
+    pub fn greater_than<K: Into<String>, V: Into<Configuration>>(key: K, value: V) -> Self {
+        Self::GreaterThan(key.into(), value.into())
+    }
+
+    /// Returns a condition that is met when `key` is greater than or equal to
+    /// `value`.
+    pub fn greater_than_or_equal<K: Into<String>, V: Into<Configuration>>(
+        key: K,
+        value: V,
+    ) -> Self {
+        Self::GreaterThanOrEqual(key.into(), value.into())
+    }
+
+    /// Returns a condition that is met when `key` is a string starting with
+    /// `prefix`.
+    pub fn starts_with<K: Into<String>, P: Into<String>>(key: K, prefix: P) -> Self {
+        Self::StartsWith(key.into(), prefix.into())
+    }
+
+    /// Returns a condition that is met when `key` is present.
+    pub fn exists<K: Into<String>>(key: K) -> Self {
+        Self::Exists(key.into())
+    }
+
+    /// Returns a condition that is met when both this condition and `other`
+    /// are met.
+    #[must_use]
+    pub fn and(self, other: Self) -> Self {
+        match self {
+            Self::All(mut conditions) => {
+                conditions.push(other);
+                Self::All(conditions)
+            }
+            condition => Self::All(vec![condition, other]),
+        }
+    }
+
+    /// Returns a condition that is met when either this condition or `other`
+    /// is met.
+    #[must_use]
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            Self::Any(mut conditions) => {
+                conditions.push(other);
+                Self::Any(conditions)
+            }
+            condition => Self::Any(vec![condition, other]),
+        }
+    }
+
+    /// Evaluates this condition against `context`.
+    #[must_use]
+    pub fn evaluate(&self, context: &Context) -> bool {
+        match self {
+            Self::Equals(key, value) => context.compare(key, value) == Some(Ordering::Equal),
+            Self::NotEquals(key, value) => matches!(
+                context.compare(key, value),
+                Some(Ordering::Less | Ordering::Greater)
+            ),
+            Self::LessThan(key, value) => context.compare(key, value) == Some(Ordering::Less),
+            Self::LessThanOrEqual(key, value) => matches!(
+                context.compare(key, value),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+            Self::GreaterThan(key, value) => context.compare(key, value) == Some(Ordering::Greater),
+            Self::GreaterThanOrEqual(key, value) => matches!(
+                context.compare(key, value),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            Self::StartsWith(key, prefix) => matches!(
+                context.get(key),
+                Some(Configuration::String(value)) if value.starts_with(prefix.as_str())
+            ),
+            Self::Exists(key) => context.get(key).is_some(),
+            Self::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.evaluate(context)),
+            Self::Any(conditions) => conditions
+                .iter()
+                .any(|condition| condition.evaluate(context)),
+            Self::Not(condition) => !condition.evaluate(context),
+        }
+    }
+}
+
+/// Returns a condition that is met when this condition is not met.
+impl std::ops::Not for Condition {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+/// Values describing a request, used to evaluate [`Condition`]s.
+///
+/// A context also provides the time used to evaluate
+/// [`Statement::valid_from`](crate::Statement::valid_from) and
+/// [`Statement::valid_until`](crate::Statement::valid_until). If no time is
+/// set, the current time is used.
+#[derive(Default, Debug, Clone)]
+pub struct Context {
+    values: HashMap<String, Configuration>,
+    time: Option<SystemTime>,
+}
+
+impl Context {
+    /// Sets the time to evaluate statements at and returns self.
+    #[must_use]
+    pub fn at(mut self, time: SystemTime) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// Returns the time to evaluate statements at. If no time was set,
+    /// [`SystemTime::now()`] is returned.
+    #[must_use]
+    pub fn time(&self) -> SystemTime {
+        self.time.unwrap_or_else(SystemTime::now)
+    }
+
+    /// Sets `key` to `value`.
+    pub fn insert<K: Into<String>, V: Into<Configuration>>(&mut self, key: K, value: V) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    /// Sets `key` to `value` and returns self.
+    #[must_use]
+    pub fn with<K: Into<String>, V: Into<Configuration>>(mut self, key: K, value: V) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Returns the value of `key`, if present.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Configuration> {
+        self.values.get(key)
+    }
+
+    fn compare(&self, key: &str, value: &Configuration) -> Option<Ordering> {
+        let ours = self.get(key)?;
+        match (ours, value) {
+            (Configuration::String(ours), Configuration::String(value)) => Some(ours.cmp(value)),
+            _ => Some(as_integer(ours)?.cmp(&as_integer(value)?)),
+        }
+    }
+}
+
+fn as_integer(value: &Configuration) -> Option<i128> {
+    match value {
+        Configuration::Unsigned(unsigned) => Some(i128::from(*unsigned)),
+        Configuration::Signed(signed) => Some(i128::from(*signed)),
+        Configuration::String(string) => string.parse().ok(),
+    }
+}
+
+#[test]
+fn condition_tests() {
+    let context = Context::default()
+        .with("hour", 13_u64)
+        .with("offset", -2_i64)
+        .with("source", "10.0.0.42")
+        .with("count", "7");
+
+    assert!(Condition::equals("hour", 13_u64).evaluate(&context));
+    assert!(Condition::equals("hour", 13_i64).evaluate(&context));
+    assert!(Condition::equals("count", 7_u64).evaluate(&context));
+    assert!(!Condition::equals("count", "07").evaluate(&context));
+    assert!(Condition::not_equals("hour", 12_u64).evaluate(&context));
+    assert!(!Condition::not_equals("missing", 12_u64).evaluate(&context));
+    assert!(Condition::less_than("offset", 0_u64).evaluate(&context));
+    assert!(Condition::greater_than_or_equal("hour", 9_u64)
+        .and(Condition::less_than("hour", 17_u64))
+        .evaluate(&context));
+    assert!(!Condition::greater_than("hour", 13_u64).evaluate(&context));
+    assert!(Condition::less_than_or_equal("hour", 13_u64).evaluate(&context));
+    assert!(Condition::starts_with("source", "10.").evaluate(&context));
+    assert!(!Condition::starts_with("hour", "1").evaluate(&context));
+    assert!(Condition::exists("source").evaluate(&context));
+    assert!(!Condition::exists("missing").evaluate(&context));
+    assert!(Condition::exists("missing")
+        .or(Condition::exists("hour"))
+        .evaluate(&context));
+    assert!((!Condition::exists("missing")).evaluate(&context));
+    // Values that can't be compared never match.
+    assert!(!Condition::less_than("source", 5_u64).evaluate(&context));
+    assert!(!Condition::not_equals("source", 5_u64).evaluate(&context));
+}
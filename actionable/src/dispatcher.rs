@@ -11,6 +11,13 @@ pub trait Dispatcher<T>: Send + Sync {
     /// Dispatches `request` to the appropriate handler while also ensuring
     /// `permissions` allows the request.
     fn dispatch(&self, permissions: &Permissions, request: T) -> Self::Result;
+
+    /// Dispatches `request` to the appropriate handler while also ensuring
+    /// that every entry in `permissions` allows the request. See
+    /// [`Permissions::intersection()`].
+    fn dispatch_with_all(&self, permissions: &[&Permissions], request: T) -> Self::Result {
+        self.dispatch(&Permissions::intersection_of(permissions), request)
+    }
 }
 
 /// Dispatches `T` to an appropriate handler. This trait is derivable.
@@ -22,4 +29,15 @@ pub trait AsyncDispatcher<T>: Send + Sync {
     /// Dispatches `request` to the appropriate handler while also ensuring
     /// `permissions` allows the request.
     async fn dispatch(&self, permissions: &Permissions, request: T) -> Self::Result;
+
+    /// Dispatches `request` to the appropriate handler while also ensuring
+    /// that every entry in `permissions` allows the request. See
+    /// [`Permissions::intersection()`].
+    async fn dispatch_with_all(&self, permissions: &[&Permissions], request: T) -> Self::Result
+    where
+        T: Send + 'async_trait,
+    {
+        self.dispatch(&Permissions::intersection_of(permissions), request)
+            .await
+    }
 }
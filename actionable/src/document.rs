@@ -0,0 +1,478 @@
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::Formatter,
+    time::{Duration, SystemTime},
+};
+
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    ActionName, ActionNameList, Condition, Configuration, Effect, ResourceName, Statement,
+};
+
+/// A wrapper that serializes its contents in a human-friendly document format,
+/// similar to policies in IAM. This format is opt-in: [`Statement`],
+/// [`ActionNameList`] and [`Configuration`] use serde's default representation
+/// unless wrapped in a `Document`.
+///
+/// In this format:
+///
+/// * A [`Statement`] is a map whose `resources` are a list of strings in the
+///   [`ResourceName` text format](ResourceName#text-format). `effect`,
+///   `actions`, `condition`, `valid_from`, `valid_until` and `configuration`
+///   may be omitted. Unknown fields are rejected.
+/// * An [`ActionNameList`] is either `"*"` for [`ActionNameList::All`], a list
+///   of action names such as `["User.Delete", "Post.*"]`, or a single action
+///   name.
+/// * A [`Configuration`] is a native integer or string.
+/// * A [`Condition`] is a map with a single entry, whose key is the name of the
+///   condition's variant. Comparisons contain a list of the context key and a
+///   native value, such as `{ "LessThan": ["hour", 17] }`. `Exists` contains
+///   the key, `StartsWith` contains a list of the key and prefix, `All` and
+///   `Any` contain a list of conditions and `Not` contains a condition.
+/// * `valid_from` and `valid_until` are the number of seconds since the Unix
+///   epoch. When serializing, `valid_from` is rounded up and `valid_until` is
+///   rounded down to a whole second, so the period never grows.
+///
+/// For example, a statement in JSON might look like:
+///
+/// ```json
+/// {
+///     "effect": "Allow",
+///     "resources": ["users.*"],
+///     "actions": ["User.Read", "User.ChangeUsername"],
+///     "condition": { "All": [
+///         { "GreaterThanOrEqual": ["hour", 9] },
+///         { "LessThan": ["hour", 17] }
+///     ] },
+///     "valid_until": 1767225600,
+///     "configuration": { "rate-limit": 500 }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Document<T>(pub T);
+
+impl<T> From<T> for Document<T> {
+    fn from(contents: T) -> Self {
+        Self(contents)
+    }
+}
+
+impl Serialize for Document<Configuration> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.0 {
+            Configuration::Unsigned(unsigned) => serializer.serialize_u64(*unsigned),
+            Configuration::Signed(signed) => serializer.serialize_i64(*signed),
+            Configuration::String(string) => serializer.serialize_str(string),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Document<Configuration> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ConfigurationVisitor)
+    }
+}
+
+struct ConfigurationVisitor;
+
+impl<'de> Visitor<'de> for ConfigurationVisitor {
+    type Value = Document<Configuration>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("an integer or string")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(Document(Configuration::Unsigned(v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match u64::try_from(v) {
+            Ok(unsigned) => self.visit_u64(unsigned),
+            Err(_) => Ok(Document(Configuration::Signed(v))),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(Document(Configuration::String(v.to_string())))
+    }
+}
+
+impl Serialize for Document<ActionNameList> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.0 {
+            ActionNameList::All => serializer.serialize_str("*"),
+            ActionNameList::List(names) => {
+                let mut seq = serializer.serialize_seq(Some(names.len()))?;
+                for name in names {
+                    seq.serialize_element(&name.to_string())?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Document<ActionNameList> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ActionNameListVisitor)
+    }
+}
+
+struct ActionNameListVisitor;
+
+impl<'de> Visitor<'de> for ActionNameListVisitor {
+    type Value = Document<ActionNameList>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("\"*\", an action name or a list of action names")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        if v == "*" {
+            Ok(Document(ActionNameList::All))
+        } else {
+            parse_action_name(v).map(|name| Document(ActionNameList::List(vec![name])))
+        }
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut names = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(name) = seq.next_element::<String>()? {
+            names.push(parse_action_name(&name)?);
+        }
+        Ok(Document(ActionNameList::List(names)))
+    }
+}
+
+fn parse_action_name<E: Error>(name: &str) -> Result<ActionName, E> {
+    if name.is_empty() {
+        Err(E::custom("action names must not be empty"))
+    } else {
+        name.parse().map_err(E::custom)
+    }
+}
+
+impl<'a> Serialize for Document<ResourceName<'a>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Document<ResourceName<'static>> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map(Document).map_err(D::Error::custom)
+    }
+}
+
+/// The document representation of a [`Condition`].
+#[derive(Serialize, Deserialize)]
+enum ConditionDocument {
+    Equals(String, Document<Configuration>),
+    NotEquals(String, Document<Configuration>),
+    LessThan(String, Document<Configuration>),
+    LessThanOrEqual(String, Document<Configuration>),
+    GreaterThan(String, Document<Configuration>),
+    GreaterThanOrEqual(String, Document<Configuration>),
+    StartsWith(String, String),
+    Exists(String),
+    All(Vec<ConditionDocument>),
+    Any(Vec<ConditionDocument>),
+    Not(Box<ConditionDocument>),
+}
+
+impl From<Condition> for ConditionDocument {
+    fn from(condition: Condition) -> Self {
+        match condition {
+            Condition::Equals(key, value) => Self::Equals(key, Document(value)),
+            Condition::NotEquals(key, value) => Self::NotEquals(key, Document(value)),
+            Condition::LessThan(key, value) => Self::LessThan(key, Document(value)),
+            Condition::LessThanOrEqual(key, value) => Self::LessThanOrEqual(key, Document(value)),
+            Condition::GreaterThan(key, value) => Self::GreaterThan(key, Document(value)),
+            Condition::GreaterThanOrEqual(key, value) =>
+                Self::GreaterThanOrEqual(key, Document(value)),
+            Condition::StartsWith(key, prefix) => Self::StartsWith(key, prefix),
+            Condition::Exists(key) => Self::Exists(key),
+            Condition::All(conditions) =>
+                Self::All(conditions.into_iter().map(Self::from).collect()),
+            Condition::Any(conditions) =>
+                Self::Any(conditions.into_iter().map(Self::from).collect()),
+            Condition::Not(condition) => Self::Not(Box::new(Self::from(*condition))),
+        }
+    }
+}
+
+impl From<ConditionDocument> for Condition {
+    fn from(document: ConditionDocument) -> Self {
+        match document {
+            ConditionDocument::Equals(key, value) => Self::Equals(key, value.0),
+            ConditionDocument::NotEquals(key, value) => Self::NotEquals(key, value.0),
+            ConditionDocument::LessThan(key, value) => Self::LessThan(key, value.0),
+            ConditionDocument::LessThanOrEqual(key, value) => Self::LessThanOrEqual(key, value.0),
+            ConditionDocument::GreaterThan(key, value) => Self::GreaterThan(key, value.0),
+            ConditionDocument::GreaterThanOrEqual(key, value) =>
+                Self::GreaterThanOrEqual(key, value.0),
+            ConditionDocument::StartsWith(key, prefix) => Self::StartsWith(key, prefix),
+            ConditionDocument::Exists(key) => Self::Exists(key),
+            ConditionDocument::All(conditions) =>
+                Self::All(conditions.into_iter().map(Self::from).collect()),
+            ConditionDocument::Any(conditions) =>
+                Self::Any(conditions.into_iter().map(Self::from).collect()),
+            ConditionDocument::Not(condition) => Self::Not(Box::new(Self::from(*condition))),
+        }
+    }
+}
+
+impl Serialize for Document<Condition> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ConditionDocument::from(self.0.clone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Document<Condition> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ConditionDocument::deserialize(deserializer).map(|document| Document(document.into()))
+    }
+}
+
+/// Returns the number of seconds between the Unix epoch and `time`, rounding
+/// up if `round_up` is true and down otherwise. Returns None if `time` is
+/// before the Unix epoch.
+fn unix_seconds(time: SystemTime, round_up: bool) -> Option<u64> {
+    let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    if round_up && since_epoch.subsec_nanos() > 0 {
+        since_epoch.as_secs().checked_add(1)
+    } else {
+        Some(since_epoch.as_secs())
+    }
+}
+
+fn serialize_timestamp<E: serde::ser::Error>(
+    time: Option<SystemTime>,
+    round_up: bool,
+) -> Result<Option<u64>, E> {
+    time.map(|time| {
+        unix_seconds(time, round_up)
+            .ok_or_else(|| E::custom("timestamps must not be before the Unix epoch"))
+    })
+    .transpose()
+}
+
+fn deserialize_timestamp<E: Error>(seconds: Option<u64>) -> Result<Option<SystemTime>, E> {
+    seconds
+        .map(|seconds| {
+            SystemTime::UNIX_EPOCH
+                .checked_add(Duration::from_secs(seconds))
+                .ok_or_else(|| E::custom("timestamp is out of range"))
+        })
+        .transpose()
+}
+
+/// The document representation of a [`Statement`].
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StatementDocument {
+    #[serde(default)]
+    effect: Effect,
+    resources: Vec<Document<ResourceName<'static>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actions: Option<Document<ActionNameList>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    condition: Option<Document<Condition>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_from: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_until: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    configuration: Option<HashMap<String, Document<Configuration>>>,
+}
+
+impl Serialize for Document<Statement> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let statement = self.0.clone();
+        StatementDocument {
+            effect: statement.effect,
+            resources: statement.resources.into_iter().map(Document).collect(),
+            actions: statement.actions.map(Document),
+            condition: statement.condition.map(Document),
+            valid_from: serialize_timestamp(statement.valid_from, true)?,
+            valid_until: serialize_timestamp(statement.valid_until, false)?,
+            configuration: statement.configuration.map(|configuration| {
+                configuration
+                    .into_iter()
+                    .map(|(key, value)| (key, Document(value)))
+                    .collect()
+            }),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Document<Statement> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let document = StatementDocument::deserialize(deserializer)?;
+        Ok(Document(Statement {
+            resources: document
+                .resources
+                .into_iter()
+                .map(|resource| resource.0)
+                .collect(),
+            actions: document.actions.map(|actions| actions.0),
+            effect: document.effect,
+            condition: document.condition.map(|condition| condition.0),
+            valid_from: deserialize_timestamp(document.valid_from)?,
+            valid_until: deserialize_timestamp(document.valid_until)?,
+            configuration: document.configuration.map(|configuration| {
+                configuration
+                    .into_iter()
+                    .map(|(key, value)| (key, value.0))
+                    .collect()
+            }),
+        }))
+    }
+}
+
+impl Serialize for Document<Vec<Statement>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for statement in &self.0 {
+            seq.serialize_element(&Document(statement.clone()))?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Document<Vec<Statement>> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let statements = Vec::<Document<Statement>>::deserialize(deserializer)?;
+        Ok(Document(
+            statements
+                .into_iter()
+                .map(|statement| statement.0)
+                .collect(),
+        ))
+    }
+}
+
+#[test]
+fn document_tests() {
+    use serde::de::{
+        value::{Error, MapDeserializer, SeqDeserializer},
+        IntoDeserializer,
+    };
+
+    fn actions<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+        Document::<ActionNameList>::deserialize(deserializer).map(|actions| match actions.0 {
+            ActionNameList::All => vec![String::from("*")],
+            ActionNameList::List(names) => names.iter().map(ToString::to_string).collect(),
+        })
+    }
+
+    fn configuration<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Configuration, D::Error> {
+        Document::<Configuration>::deserialize(deserializer).map(|configuration| configuration.0)
+    }
+
+    assert_eq!(
+        actions(IntoDeserializer::<Error>::into_deserializer("*")).unwrap(),
+        ["*"]
+    );
+    assert_eq!(
+        actions(IntoDeserializer::<Error>::into_deserializer("User.Delete")).unwrap(),
+        ["User.Delete"]
+    );
+    assert_eq!(
+        actions(SeqDeserializer::<_, Error>::new(
+            vec!["User.Delete", "*.Read"].into_iter()
+        ))
+        .unwrap(),
+        ["User.Delete", "*.Read"]
+    );
+    assert!(actions(IntoDeserializer::<Error>::into_deserializer("")).is_err());
+    assert!(actions(SeqDeserializer::<_, Error>::new(
+        vec!["User..Delete"].into_iter()
+    ))
+    .is_err());
+    assert!(actions(IntoDeserializer::<Error>::into_deserializer(1_u64)).is_err());
+
+    assert!(matches!(
+        configuration(IntoDeserializer::<Error>::into_deserializer(500_i64)),
+        Ok(Configuration::Unsigned(500))
+    ));
+    assert!(matches!(
+        configuration(IntoDeserializer::<Error>::into_deserializer(-1_i64)),
+        Ok(Configuration::Signed(-1))
+    ));
+    assert!(matches!(
+        configuration(IntoDeserializer::<Error>::into_deserializer("hello")),
+        Ok(Configuration::String(value)) if value == "hello"
+    ));
+    assert!(configuration(IntoDeserializer::<Error>::into_deserializer(true)).is_err());
+    assert!(configuration(IntoDeserializer::<Error>::into_deserializer(1.5_f64)).is_err());
+
+    // Unknown fields are rejected.
+    let error = Document::<Statement>::deserialize(MapDeserializer::<_, Error>::new(
+        vec![("effect", "Deny"), ("resource", "users.*")].into_iter(),
+    ))
+    .unwrap_err();
+    assert!(error.to_string().contains("unknown field `resource`"));
+}
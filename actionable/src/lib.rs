@@ -10,7 +10,9 @@
 //! The [`Permissions`] struct is constructed from a list of `Statement`s. The
 //! `Statement` struct is inspired by [statements in
 //! IAM](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_elements_statement.html).
-//! By default, all actions are denied for all resources.
+//! By default, all actions are denied for all resources. Statements can also
+//! explicitly deny actions, which takes priority over any statement allowing
+//! them.
 //!
 //! The [`ResourceName`] struct describes a unique name/id of *anything* in your
 //! application. This is meant to be similar to [ARNs in
@@ -20,8 +22,9 @@
 //!
 //! The [`Action`] trait is derive-able, and will convert any enum to something
 //! that can be permitted or denied to any [`ResourceName`]. This derive macro
-//! only supports enums with variants that have no parameters, or only have a
-//! single name-less parameter that also implements [`Action`].
+//! supports enums with variants that have no parameters, or that have a single
+//! parameter that also implements [`Action`]. Additional parameters can be
+//! excluded from the action name using `#[action(skip)]`.
 //!
 //! An example [`Action`] enum might look like:
 //!
@@ -105,8 +108,11 @@
 #![cfg_attr(doc, deny(rustdoc::all))]
 
 mod action;
+mod condition;
 mod dispatcher;
+mod document;
 mod permissions;
+mod roles;
 mod statement;
 
 pub use actionable_macros::Actionable;
@@ -115,10 +121,16 @@ pub use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 pub use self::{
-    action::{Action, ActionName},
+    action::{Action, ActionName, ActionNameError, ActionRegistry},
+    condition::{Condition, Context},
     dispatcher::{AsyncDispatcher, Dispatcher},
-    permissions::Permissions,
-    statement::{ActionNameList, Configuration, Identifier, ResourceName, Statement},
+    document::Document,
+    permissions::{Decision, MatchedStatement, Permissions},
+    roles::{Role, RoleError, RoleRegistry},
+    statement::{
+        ActionNameList, Configuration, Effect, Identifier, ResourceName, ResourceNameError,
+        Statement,
+    },
 };
 
 #[cfg(test)]
@@ -1,24 +1,95 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{borrow::Cow, collections::HashMap, sync::Arc, time::SystemTime};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    statement::Configuration, Action, ActionNameList, Identifier, PermissionDenied, ResourceName,
-    Statement,
+    statement::{combinations, Configuration},
+    Action, ActionName, ActionNameList, Context, Effect, Identifier, PermissionDenied,
+    ResourceName, Statement,
 };
 
 /// A collection of allowed permissions. This is constructed from a
 /// `Vec<`[`Statement`]`>`. By default, no actions are allowed on any resources.
+///
+/// Statements with an [`Effect::Deny`] take priority over all other
+/// statements: if any statement matching a resource denies an action, the
+/// action is denied even if another matching statement allows it.
+///
+/// Statements with a [`Condition`](crate::Condition) only apply when their
+/// condition is met by the [`Context`] of the request. Methods that don't
+/// accept a [`Context`] evaluate conditions against an empty context.
+/// Similarly, statements with [`valid_from`](Statement::valid_from) or
+/// [`valid_until`](Statement::valid_until) only apply while
+/// [`Context::time()`] is within that period, and resource names containing
+/// an [`Identifier::Variable`] only match the value of that variable in the
+/// [`Context`].
+///
+/// Permissions can be combined using [`Permissions::merged()`], which allows
+/// an action if any of the permissions allow it, or
+/// [`Permissions::intersection()`], which only allows an action if all of the
+/// permissions allow it.
+///
+/// By default, identifiers are matched using [`Identifier`]'s equality, where
+/// an [`Identifier::Integer`] is equal to an [`Identifier::String`] or
+/// [`Identifier::Bytes`] with the same byte representation.
+/// [`Permissions::with_strict_identifiers()`] opts into only matching
+/// identifiers of the same variant.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Permissions {
     data: Arc<Data>,
+    /// Permissions that were [merged](Self::merged) with `data` but can't be
+    /// combined with it, such as intersections. An action is allowed if
+    /// `data` or any of these permissions allow it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    alternatives: Vec<Permissions>,
+    /// Additional permissions that must also allow an action for it to be
+    /// allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    intersecting: Vec<Permissions>,
+    /// If true, identifiers are compared using [`Identifier::eq_strict()`].
+    #[serde(default)]
+    strict_identifiers: bool,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 struct Data {
-    children: Option<HashMap<Identifier<'static>, Data>>,
+    /// The children of this node, grouped by [`Identifier`]'s equality. Each
+    /// group contains one node for each identifier variant, so that strict
+    /// matching can tell them apart.
+    children: Option<HashMap<Identifier<'static>, Vec<(Identifier<'static>, Data)>>>,
+    /// The identifiers in `children` that can't be looked up by hash, such as
+    /// [`Identifier::Pattern`].
+    #[serde(default)]
+    patterns: Vec<Identifier<'static>>,
     allowed: AllowedActions,
+    #[serde(default)]
+    denied: AllowedActions,
     configuration: Option<HashMap<String, Configuration>>,
+    /// The statements that apply to this node.
+    #[serde(default)]
+    statements: Vec<Arc<Statement>>,
+}
+
+/// How a resource name is matched against the nodes in [`Data`].
+#[derive(Clone, Copy)]
+struct Matching {
+    /// If true, identifiers are compared using [`Identifier::eq_strict()`].
+    strict: bool,
+    /// If true, the resource name is a path written in a statement, such as
+    /// when comparing two sets of permissions, and an [`Identifier::Variable`]
+    /// in it matches the same variable. Otherwise, the resource name was
+    /// requested, and a requested variable never matches a variable in a
+    /// statement, as the requester doesn't control the variable's value.
+    written: bool,
+}
+
+impl Matching {
+    /// Returns true if the child for `identifier` matches the `requested`
+    /// identifier, which is known to be equal to it.
+    fn matches(self, identifier: &Identifier<'_>, requested: &Identifier<'_>) -> bool {
+        (!self.strict || identifier.eq_strict(requested))
+            && (self.written || !matches!(identifier, Identifier::Variable(_)))
+    }
 }
 
 impl Permissions {
@@ -29,6 +100,32 @@ impl Permissions {
         Self::from(vec![Statement::allow_all_for_any_resource()])
     }
 
+    /// Returns these permissions with strict identifier matching enabled.
+    ///
+    /// With strict matching, identifiers only match identifiers of the same
+    /// variant, as compared by [`Identifier::eq_strict()`]. For example, a
+    /// statement for `Identifier::Integer(7522537965574647666)` no longer
+    /// matches `Identifier::from("helloooo")`, even though their byte
+    /// representations are equal. [`Identifier::Pattern`]s only match
+    /// [`Identifier::String`]s, [`Identifier::Range`]s only match
+    /// [`Identifier::Integer`]s, and [`Identifier::Variable`]s only match
+    /// values of the same type as their value in the [`Context`].
+    #[must_use]
+    pub fn with_strict_identifiers(mut self) -> Self {
+        self.strict_identifiers = true;
+        for permissions in self.alternatives.iter_mut().chain(&mut self.intersecting) {
+            *permissions = std::mem::take(permissions).with_strict_identifiers();
+        }
+        self
+    }
+
+    /// Returns true if these permissions use [strict identifier
+    /// matching](Self::with_strict_identifiers).
+    #[must_use]
+    pub const fn uses_strict_identifiers(&self) -> bool {
+        self.strict_identifiers
+    }
+
     /// Evaluate whether the `action` is allowed to be taken upon
     /// `resource_name`. Returns `Ok` if permission is allowed.
     ///
@@ -40,7 +137,23 @@ impl Permissions {
         resource_name: R,
         action: &P,
     ) -> Result<(), PermissionDenied> {
-        if self.data.allowed_to(resource_name.as_ref(), action) {
+        self.check_with(resource_name, action, &Context::default())
+    }
+
+    /// Evaluate whether the `action` is allowed to be taken upon
+    /// `resource_name`, evaluating statement conditions against `context`.
+    /// Returns `Ok` if permission is allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PermissionDenied` if permission is now allowed.
+    pub fn check_with<'a, R: AsRef<[Identifier<'a>]>, P: Action>(
+        &self,
+        resource_name: R,
+        action: &P,
+        context: &Context,
+    ) -> Result<(), PermissionDenied> {
+        if self.allowed_to_with(resource_name.as_ref(), action, context) {
             Ok(())
         } else {
             Err(PermissionDenied {
@@ -59,29 +172,591 @@ impl Permissions {
         resource_name: R,
         action: &P,
     ) -> bool {
-        self.data.allowed_to(resource_name, action)
+        self.allowed_to_with(resource_name, action, &Context::default())
+    }
+
+    /// Evaluate whether the `action` is allowed to be taken upon
+    /// `resource_name`, evaluating statement conditions against `context`.
+    /// Returns true if the action should be allowed.
+    pub fn allowed_to_with<'a, R: AsRef<[Identifier<'a>]>, P: Action>(
+        &self,
+        resource_name: R,
+        action: &P,
+        context: &Context,
+    ) -> bool {
+        self.allows(resource_name.as_ref(), action, context, false)
+    }
+
+    /// Returns true if `action` is allowed upon `resource_name`. If `written`
+    /// is true, `resource_name` is matched as it would be written in a
+    /// statement. See [`Matching::written`].
+    fn allows<P: Action>(
+        &self,
+        resource_name: &[Identifier<'_>],
+        action: &P,
+        context: &Context,
+        written: bool,
+    ) -> bool {
+        let name = action.name();
+        let allowed = self.data.allowed_to(
+            resource_name,
+            &name,
+            &action.implied_by(),
+            context,
+            self.matching(written),
+        );
+        let allowed = if self.alternatives.is_empty() {
+            allowed
+        } else {
+            // Denied actions take priority over allowed actions from any of
+            // the merged permissions.
+            !self.explicitly_denies(resource_name, &name, context, written)
+                && (allowed
+                    || self.alternatives.iter().any(|permissions| {
+                        permissions.allows(resource_name, action, context, written)
+                    }))
+        };
+        allowed
+            && self
+                .intersecting
+                .iter()
+                .all(|permissions| permissions.allows(resource_name, action, context, written))
+    }
+
+    const fn matching(&self, written: bool) -> Matching {
+        Matching {
+            strict: self.strict_identifiers,
+            written,
+        }
+    }
+
+    /// Returns the statement that explicitly denies `action` upon
+    /// `resource_name`, including statements in `alternatives`. Denials from
+    /// `intersecting` permissions aren't included, as they only limit the
+    /// permissions they're intersected with.
+    fn explicit_denial(
+        &self,
+        resource_name: &[Identifier<'_>],
+        action: &ActionName,
+        context: &Context,
+        written: bool,
+    ) -> Option<MatchedStatement> {
+        match self
+            .data
+            .explain(resource_name, action, context, self.matching(written))
+        {
+            Decision::Denied(matched) => Some(matched),
+            _ => self.alternatives.iter().find_map(|permissions| {
+                permissions.explicit_denial(resource_name, action, context, written)
+            }),
+        }
+    }
+
+    fn explicitly_denies(
+        &self,
+        resource_name: &[Identifier<'_>],
+        action: &ActionName,
+        context: &Context,
+        written: bool,
+    ) -> bool {
+        self.data
+            .any_matching(resource_name, context, self.matching(written), &|data| {
+                data.applies(Effect::Deny, action, context)
+            })
+            || self.alternatives.iter().any(|permissions| {
+                permissions.explicitly_denies(resource_name, action, context, written)
+            })
+    }
+
+    /// Evaluates whether the `action` is allowed to be taken upon
+    /// `resource_name`, returning the [`Decision`] along with the resource
+    /// path and [`Statement`] that produced it.
+    #[must_use]
+    pub fn explain<'a, R: AsRef<[Identifier<'a>]>, P: Action>(
+        &self,
+        resource_name: R,
+        action: &P,
+    ) -> Decision {
+        self.explain_with(resource_name, action, &Context::default())
+    }
+
+    /// Evaluates whether the `action` is allowed to be taken upon
+    /// `resource_name` with statement conditions evaluated against `context`,
+    /// returning the [`Decision`] along with the resource path and
+    /// [`Statement`] that produced it.
+    #[must_use]
+    pub fn explain_with<'a, R: AsRef<[Identifier<'a>]>, P: Action>(
+        &self,
+        resource_name: R,
+        action: &P,
+        context: &Context,
+    ) -> Decision {
+        self.explain_in(resource_name.as_ref(), action, context, false)
+    }
+
+    /// Returns the [`Decision`] for `action` upon `resource_name`. If
+    /// `written` is true, `resource_name` is matched as it would be written
+    /// in a statement. See [`Matching::written`].
+    fn explain_in<P: Action>(
+        &self,
+        resource_name: &[Identifier<'_>],
+        action: &P,
+        context: &Context,
+        written: bool,
+    ) -> Decision {
+        let matching = self.matching(written);
+        let mut decision = self
+            .data
+            .explain(resource_name, &action.name(), context, matching);
+        if matches!(decision, Decision::NotAllowed) {
+            // An action that implies this action may allow it.
+            for implied_by in action.implied_by() {
+                let implying_decision =
+                    self.data
+                        .explain(resource_name, &implied_by, context, matching);
+                if implying_decision.is_allowed() {
+                    decision = implying_decision;
+                    break;
+                }
+            }
+        }
+        if !self.alternatives.is_empty() && !matches!(decision, Decision::Denied(_)) {
+            if let Some(matched) =
+                self.explicit_denial(resource_name, &action.name(), context, written)
+            {
+                decision = Decision::Denied(matched);
+            } else if !decision.is_allowed() {
+                if let Some(allowed) = self
+                    .alternatives
+                    .iter()
+                    .map(|permissions| {
+                        permissions.explain_in(resource_name, action, context, written)
+                    })
+                    .find(Decision::is_allowed)
+                {
+                    decision = allowed;
+                }
+            }
+        }
+        if decision.is_allowed() {
+            // Report the first intersecting permissions that doesn't allow
+            // this action.
+            for permissions in &self.intersecting {
+                let intersecting_decision =
+                    permissions.explain_in(resource_name, action, context, written);
+                if !intersecting_decision.is_allowed() {
+                    return intersecting_decision;
+                }
+            }
+        }
+        decision
+    }
+
+    /// Returns the list of actions allowed upon `resource_name`. This combines
+    /// the actions allowed by every statement matching `resource_name`,
+    /// including statements for [`Identifier::Any`].
+    ///
+    /// Actions that are explicitly denied are removed from the returned list.
+    /// If a denied action is more specific than an allowed action name (e.g.,
+    /// `Post` is allowed but `Post.Delete` is denied), the allowed name is
+    /// still returned. Use [`Permissions::allowed_to()`] to check a specific
+    /// action.
+    ///
+    /// Statements with a condition are not included. Actions that are only
+    /// allowed because an action implying them is allowed, as described by
+    /// [`Action::implied_by()`], are not included either, as the returned
+    /// names don't know which [`Action`] type they belong to.
+    #[must_use]
+    pub fn allowed_actions<'a, R: AsRef<[Identifier<'a>]>>(
+        &self,
+        resource_name: R,
+    ) -> ActionNameList {
+        self.effective_actions(resource_name.as_ref(), false)
+            .to_action_names()
+    }
+
+    fn effective_actions(&self, resource_name: &[Identifier<'_>], written: bool) -> AllowedActions {
+        let mut allowed = self
+            .data
+            .effective_actions(resource_name, self.matching(written));
+        if !self.alternatives.is_empty() {
+            for permissions in &self.alternatives {
+                allowed.add_allowed(&permissions.effective_actions(resource_name, written));
+            }
+            allowed.remove_denied(&self.explicit_denials(resource_name, written));
+        }
+        for permissions in &self.intersecting {
+            allowed.retain_allowed(&permissions.effective_actions(resource_name, written));
+        }
+        allowed
+    }
+
+    /// Returns the actions explicitly denied upon `resource_name` by `data` or
+    /// `alternatives`.
+    fn explicit_denials(&self, resource_name: &[Identifier<'_>], written: bool) -> AllowedActions {
+        let mut denied = self
+            .data
+            .matching_actions(resource_name, self.matching(written))
+            .1;
+        for permissions in &self.alternatives {
+            denied.add_allowed(&permissions.explicit_denials(resource_name, written));
+        }
+        denied
+    }
+
+    /// Returns the resource names that `action` is allowed upon. The returned
+    /// names may contain [`Identifier::Any`]. An empty resource name indicates
+    /// `action` is allowed on all resources.
+    ///
+    /// Only the shortest matching names are returned, as allowing an action on
+    /// a resource also allows it on all resources beneath it. Resources where
+    /// `action` is explicitly denied are excluded, but denials that only apply
+    /// to resources beneath a returned name are not reflected.
+    ///
+    /// Statements with a condition are not included. Resources where an
+    /// action that [implies](Action::implied_by) `action` is allowed are
+    /// included.
+    #[must_use]
+    pub fn resources_allowing<P: Action>(&self, action: &P) -> Vec<ResourceName<'static>> {
+        // The resource names are written in statements, so they are matched
+        // as written.
+        let matching = self.matching(true);
+        let mut resources = self.data.resources_allowing(&action.name(), matching);
+        let implied_by = action.implied_by();
+        for implied_by in &implied_by {
+            resources.extend(self.data.resources_allowing(implied_by, matching));
+        }
+        if !self.intersecting.is_empty() || !self.alternatives.is_empty() || !implied_by.is_empty()
+        {
+            // Any resource name from any of the intersecting or merged
+            // permissions could be the narrowest name allowed by all of them.
+            for permissions in self.alternatives.iter().chain(&self.intersecting) {
+                resources.extend(permissions.resources_allowing(action));
+            }
+            resources.retain(|resource| {
+                self.allows(resource.as_ref(), action, &Context::default(), true)
+            });
+        }
+        resources.sort_by_cached_key(ToString::to_string);
+        resources.dedup_by(|a, b| a.to_string() == b.to_string());
+        resources
     }
 
-    /// Looks up a configured value for `resource_name`.
+    /// Looks up a configured value for `resource_name`. For permissions
+    /// created with [`Permissions::intersection()`], only the configuration
+    /// from the first permissions is used.
     #[must_use]
     pub fn get<'a: 's, 's, R: AsRef<[Identifier<'a>]>>(
         &'s self,
         resource_name: R,
         key: &str,
     ) -> Option<&'s Configuration> {
-        self.data.get(resource_name, key)
+        self.get_with(resource_name, key, &Context::default())
+    }
+
+    /// Looks up a configured value for `resource_name`, replacing any
+    /// [`Identifier::Variable`] in statements with values from `context`.
+    #[must_use]
+    pub fn get_with<'a: 's, 's, R: AsRef<[Identifier<'a>]>>(
+        &'s self,
+        resource_name: R,
+        key: &str,
+        context: &Context,
+    ) -> Option<&'s Configuration> {
+        let resource_name = resource_name.as_ref();
+        self.data
+            .get(resource_name, key, context, self.matching(false))
+            .or_else(|| {
+                self.alternatives
+                    .iter()
+                    .find_map(|permissions| permissions.get_with(resource_name, key, context))
+            })
+    }
+
+    /// Returns the next time after now that a statement's
+    /// [`valid_from`](Statement::valid_from) or
+    /// [`valid_until`](Statement::valid_until) is reached. Once this time
+    /// passes, checks may return different results, so any cached results
+    /// should be invalidated. Returns None if no statements will begin or stop
+    /// applying in the future.
+    #[must_use]
+    pub fn next_expiry(&self) -> Option<SystemTime> {
+        self.next_expiry_after(SystemTime::now())
+    }
+
+    /// Returns the next time after `now` that a statement's
+    /// [`valid_from`](Statement::valid_from) or
+    /// [`valid_until`](Statement::valid_until) is reached. This can be used
+    /// with the time of a [`Context`] created with [`Context::at()`]. Returns
+    /// None if no statements will begin or stop applying after `now`.
+    #[must_use]
+    pub fn next_expiry_after(&self, now: SystemTime) -> Option<SystemTime> {
+        let mut next = None;
+        self.data.visit(&mut Vec::new(), &mut |_, data| {
+            next = data
+                .statements
+                .iter()
+                .flat_map(|statement| {
+                    statement
+                        .valid_from
+                        .into_iter()
+                        .chain(statement.valid_until)
+                })
+                .filter(|time| *time > now)
+                .chain(next)
+                .min();
+        });
+        for permissions in self.alternatives.iter().chain(&self.intersecting) {
+            next = next
+                .into_iter()
+                .chain(permissions.next_expiry_after(now))
+                .min();
+        }
+        next
     }
 
     /// Returns a new instance that merges all allowed actions from
-    /// `permissions`.
+    /// `permissions`. An action is allowed if any of `permissions` allow it,
+    /// unless any of `permissions` explicitly deny it.
+    ///
+    /// Permissions created with [`Permissions::intersection()`] remain
+    /// limited by the permissions they are intersected with, but those
+    /// permissions don't limit the rest of the merged result. If any of
+    /// `permissions` use [strict identifier
+    /// matching](Self::with_strict_identifiers), the merged result does too.
     #[must_use]
     pub fn merged<'a>(permissions: impl IntoIterator<Item = &'a Self>) -> Self {
         let mut combined = Data::default();
+        let mut alternatives = Vec::new();
+        let mut strict_identifiers = false;
         for incoming in permissions {
-            combined.add_permissions(&incoming.data);
+            if incoming.intersecting.is_empty() {
+                combined.add_permissions(&incoming.data);
+                alternatives.extend(incoming.alternatives.iter().cloned());
+            } else {
+                alternatives.push(incoming.clone());
+            }
+            strict_identifiers |= incoming.strict_identifiers;
         }
-        Self {
+        let merged = Self {
             data: Arc::new(combined),
+            alternatives,
+            intersecting: Vec::new(),
+            strict_identifiers: false,
+        };
+        if strict_identifiers {
+            merged.with_strict_identifiers()
+        } else {
+            merged
+        }
+    }
+
+    /// Returns true if every action allowed by these permissions is also
+    /// allowed by `other`. See [`Permissions::difference()`] for how the
+    /// permissions are compared.
+    #[must_use]
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Returns the grants in these permissions that are not allowed by
+    /// `other`. Each entry contains the resource name and action name that
+    /// `other` does not allow, along with the statement in these permissions
+    /// that granted it.
+    ///
+    /// Resource names are compared as they are written: a grant for
+    /// `posts.*` is only allowed by `other` if `other` allows the action on
+    /// `posts.*`, `posts.**`, `posts` or `*`, not if it allows the action on
+    /// every individual post. Any action `other` explicitly denies that these
+    /// permissions allow is also reported. If `other` uses [strict identifier
+    /// matching](Self::with_strict_identifiers) and these permissions don't,
+    /// `other` must allow each variant with the same byte representation as
+    /// an identifier these permissions grant, and can only allow patterns,
+    /// variables and ranges using `*` or `**`.
+    ///
+    /// Statements in these permissions with a condition or validity period are
+    /// treated as if they always apply. Statements in `other` with a condition
+    /// or validity period only allow an action if the statement granting it in
+    /// these permissions has an identical condition and validity period, while
+    /// they always deny an action.
+    ///
+    /// For permissions created with [`Permissions::intersection()`], only the
+    /// grants of the first permissions are compared, which may report grants
+    /// that the intersection doesn't actually allow.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Vec<MatchedStatement> {
+        let mut difference = Vec::new();
+        self.ungranted(other, &mut difference);
+
+        // Anything `other` explicitly denies must not be allowed by these
+        // permissions, even if the denial is more specific than what these
+        // permissions allow.
+        other.visit_denials(&mut |path, denied| {
+            let mut overlapping = self.effective_actions(path, true);
+            overlapping.retain_allowed(denied);
+            for action in overlapping.names() {
+                if let Decision::Allowed(matched) =
+                    self.explain_in(path, &action, &Context::default(), true)
+                {
+                    difference.push(MatchedStatement {
+                        resource: ResourceName::from(path.to_vec()),
+                        action,
+                        statement: matched.statement,
+                    });
+                }
+            }
+        });
+
+        difference
+    }
+
+    /// Pushes each grant in these permissions and their `alternatives` that
+    /// `other` does not allow to `difference`.
+    fn ungranted(&self, other: &Self, difference: &mut Vec<MatchedStatement>) {
+        // Every action allowed by these permissions must be allowed by `other`.
+        self.data.visit(&mut Vec::new(), &mut |path, data| {
+            let mut ungranted = data
+                .grants()
+                .into_iter()
+                .filter(|(action, grant)| {
+                    !self.data.any_matching(
+                        path,
+                        &Context::default(),
+                        self.matching(true),
+                        &|data| data.denied.allows(action),
+                    ) && !other.covers(path, action, grant, self.strict_identifiers)
+                })
+                .collect::<Vec<_>>();
+            ungranted.sort_by_cached_key(|(action, _)| action.to_string());
+            ungranted.dedup_by(|(a, _), (b, _)| a == b);
+            for (action, statement) in ungranted {
+                difference.push(MatchedStatement {
+                    resource: ResourceName::from(path.to_vec()),
+                    action,
+                    statement: statement.clone(),
+                });
+            }
+        });
+        for permissions in &self.alternatives {
+            permissions.ungranted(other, difference);
+        }
+    }
+
+    /// Returns true if these permissions allow `action` upon `resource_name`
+    /// whenever `grant` applies. Statements with a condition or validity
+    /// period only allow the action if they have the same condition and
+    /// validity period as `grant`, while they always deny it. `strict` is
+    /// whether the permissions containing `grant` use strict identifier
+    /// matching.
+    fn covers(
+        &self,
+        resource_name: &[Identifier<'_>],
+        action: &ActionName,
+        grant: &Statement,
+        strict: bool,
+    ) -> bool {
+        if !strict && self.strict_identifiers {
+            // Without strict identifiers, the grant also allows every variant
+            // with the same byte representation, each of which must be allowed.
+            // Patterns, variables and ranges match more than can be listed, so
+            // only wildcards can allow them.
+            let equivalents = resource_name
+                .iter()
+                .map(|identifier| {
+                    identifier
+                        .loose_equivalents()
+                        .unwrap_or_else(|| vec![Identifier::Any])
+                })
+                .collect();
+            return combinations(equivalents)
+                .iter()
+                .all(|resource_name| self.covers(resource_name, action, grant, true));
+        }
+
+        !self.possibly_denies(resource_name, action)
+            && (self.data.any_matching(
+                resource_name,
+                &Context::default(),
+                self.matching(true),
+                &|data| {
+                    data.applies_when(Effect::Allow, action, |statement| {
+                        statement.has_same_conditions(grant)
+                    })
+                },
+            ) || self
+                .alternatives
+                .iter()
+                .any(|permissions| permissions.covers(resource_name, action, grant, strict)))
+            && self
+                .intersecting
+                .iter()
+                .all(|permissions| permissions.covers(resource_name, action, grant, strict))
+    }
+
+    /// Returns true if any statement, regardless of its condition or validity
+    /// period, denies `action` upon `resource_name`.
+    fn possibly_denies(&self, resource_name: &[Identifier<'_>], action: &ActionName) -> bool {
+        self.data.any_matching(
+            resource_name,
+            &Context::default(),
+            self.matching(true),
+            &|data| data.applies_when(Effect::Deny, action, |_| true),
+        ) || self
+            .alternatives
+            .iter()
+            .any(|permissions| permissions.possibly_denies(resource_name, action))
+    }
+
+    fn visit_denials<F: FnMut(&[Identifier<'static>], &AllowedActions)>(&self, visitor: &mut F) {
+        self.data.visit(&mut Vec::new(), &mut |path, data| {
+            let denied = data.possibly_denied();
+            if !matches!(denied, AllowedActions::None) {
+                visitor(path, &denied);
+            }
+        });
+        for permissions in self.alternatives.iter().chain(&self.intersecting) {
+            permissions.visit_denials(visitor);
+        }
+    }
+
+    /// Returns a new instance that only allows actions that both `a` and `b`
+    /// allow. This can be used to limit a set of permissions, such as
+    /// combining a user's permissions with the permissions granted to an API
+    /// token they created.
+    #[must_use]
+    pub fn intersection(a: &Self, b: &Self) -> Self {
+        let mut intersection = a.clone();
+        intersection.intersecting.push(b.clone());
+        intersection
+    }
+
+    /// Returns these permissions limited by a permissions `boundary`. An
+    /// action is only allowed if both these permissions and `boundary` allow
+    /// it. The boundary never grants any actions on its own, and its
+    /// configuration values are not used.
+    ///
+    /// Because the result is still a `Permissions`, it can be passed anywhere
+    /// permissions are accepted, such as
+    /// [`Dispatcher::dispatch()`](crate::Dispatcher::dispatch).
+    /// When bounded permissions are [merged](Self::merged), the boundary only
+    /// limits the actions these permissions allow. To limit a merged set of
+    /// permissions, such as a user's roles, bound the merged result.
+    #[must_use]
+    pub fn bounded_by(&self, boundary: &Self) -> Self {
+        Self::intersection(self, boundary)
+    }
+
+    /// Returns a new instance that only allows actions that all of
+    /// `permissions` allow. If `permissions` is empty, no actions are allowed.
+    pub(crate) fn intersection_of(permissions: &[&Self]) -> Self {
+        if let Some((first, rest)) = permissions.split_first() {
+            let mut intersection = Self::clone(first);
+            intersection
+                .intersecting
+                .extend(rest.iter().copied().cloned());
+            intersection
+        } else {
+            Self::default()
         }
     }
 }
@@ -89,14 +764,15 @@ impl Permissions {
 impl Data {
     fn add_permissions(&mut self, permissions: &Self) {
         if let Some(children) = &permissions.children {
-            let our_children = self.children.get_or_insert_with(HashMap::new);
-            for (name, permissions) in children {
-                let our_permissions = our_children.entry(name.clone()).or_default();
-                our_permissions.add_permissions(permissions);
+            for (name, permissions) in children.values().flatten() {
+                self.child_mut(name).add_permissions(permissions);
             }
         }
 
         self.allowed.add_allowed(&permissions.allowed);
+        self.denied.add_allowed(&permissions.denied);
+        self.statements
+            .extend(permissions.statements.iter().cloned());
         if let Some(incoming_configuration) = &permissions.configuration {
             if let Some(configuration) = &mut self.configuration {
                 for (key, value) in incoming_configuration {
@@ -110,91 +786,395 @@ impl Data {
         }
     }
 
-    fn allowed_to<'a, R: AsRef<[Identifier<'a>]>, P: Action>(
+    /// Returns the child for `name`, inserting it if needed.
+    fn child_mut(&mut self, name: &Identifier<'static>) -> &mut Self {
+        let children = self.children.get_or_insert_with(HashMap::default);
+        if name.is_pattern() && !children.contains_key(name) {
+            self.patterns.push(name.clone());
+        }
+        let group = children.entry(name.clone()).or_default();
+        let index = if let Some(index) = group
+            .iter()
+            .position(|(identifier, _)| identifier.eq_strict(name))
+        {
+            index
+        } else {
+            group.push((name.clone(), Self::default()));
+            group.len() - 1
+        };
+        &mut group[index].1
+    }
+
+    /// Returns the children equal to `name`, with one child for each
+    /// identifier variant.
+    fn children_equal_to<'s>(&'s self, name: &Identifier<'s>) -> &'s [(Identifier<'static>, Self)] {
+        self.children
+            .as_ref()
+            .and_then(|children| children.get(name))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the only child for `name`, which must be an identifier that is
+    /// only equal to itself, such as [`Identifier::Any`].
+    fn child<'s>(&'s self, name: &Identifier<'s>) -> Option<&'s Self> {
+        self.children_equal_to(name).first().map(|(_, child)| child)
+    }
+
+    fn allowed_to(
         &self,
-        resource_name: R,
-        action: &P,
+        resource_name: &[Identifier<'_>],
+        action: &ActionName,
+        implied_by: &[ActionName],
+        context: &Context,
+        matching: Matching,
     ) -> bool {
-        let resource_name = resource_name.as_ref();
-        // This function checks all possible matches of `resource_name` by using
-        // recursion to call itself for each entry in `resource_name`. This
-        // first block does the function call recursion. The second block checks
-        // `action`.
-        if let Some(resource) = resource_name.first() {
-            if let Some(children) = &self.children {
-                let remaining_resource = &resource_name[1..resource_name.len()];
-                // Check if there are entries for this resource segment.
-                if let Some(permissions) = children.get(resource) {
-                    if permissions.allowed_to(remaining_resource, action) {
-                        return true;
-                    }
-                }
+        let denied = |action: &ActionName| {
+            self.any_matching(resource_name, context, matching, &|data| {
+                data.applies(Effect::Deny, action, context)
+            })
+        };
+        let allowed = |action: &ActionName| {
+            // Denied actions take priority over allowed actions, regardless of
+            // which path matched.
+            !denied(action)
+                && self.any_matching(resource_name, context, matching, &|data| {
+                    data.applies(Effect::Allow, action, context)
+                })
+        };
+        // An action that implies `action` only allows it if `action` isn't
+        // explicitly denied.
+        allowed(action) || (!denied(action) && implied_by.iter().any(allowed))
+    }
+
+    /// Returns true if a statement on this node with `effect` applies to
+    /// `action`. Statements with conditions or validity periods aren't part of
+    /// `allowed` or `denied`, so they are evaluated against `context`
+    /// individually.
+    fn applies(&self, effect: Effect, action: &ActionName, context: &Context) -> bool {
+        self.applies_when(effect, action, |statement| statement.is_met(context))
+    }
 
-                // Check if there are entries for `Any`.
-                if let Some(permissions) = children.get(&Identifier::Any) {
-                    if permissions.allowed_to(remaining_resource, action) {
-                        return true;
+    /// Returns true if a statement on this node with `effect` applies to
+    /// `action`, only including statements with conditions or validity
+    /// periods if `predicate` returns true for them.
+    fn applies_when<F: Fn(&Statement) -> bool>(
+        &self,
+        effect: Effect,
+        action: &ActionName,
+        predicate: F,
+    ) -> bool {
+        let actions = match effect {
+            Effect::Allow => &self.allowed,
+            Effect::Deny => &self.denied,
+        };
+        actions.allows(action)
+            || self.statements.iter().any(|statement| {
+                statement.effect == effect
+                    && statement.is_conditional()
+                    && match &statement.actions {
+                        Some(actions) =>
+                            actions.matching_prefix(action).is_some() && predicate(statement),
+                        None => false,
                     }
+            })
+    }
+
+    /// Returns the action names granted by each statement on this node that
+    /// allows actions, including statements with conditions or validity
+    /// periods.
+    fn grants(&self) -> Vec<(ActionName, &Statement)> {
+        let mut grants = Vec::new();
+        for statement in &self.statements {
+            if statement.effect == Effect::Allow {
+                match &statement.actions {
+                    Some(ActionNameList::All) => grants.push((ActionName::default(), &**statement)),
+                    Some(ActionNameList::List(actions)) =>
+                        grants.extend(actions.iter().map(|action| (action.clone(), &**statement))),
+                    None => {}
                 }
             }
         }
+        grants
+    }
 
-        // When execution reaches here, either resource_name is empty, or none
-        // of the previous paths have reached an "allow" state. The purpose of
-        // this chunk of code is to determine if this action is allowed based on
-        // this node's list of approved actions. This is also evaluated
-        // recursively, but at any stage if we reach match (positive or
-        // negative), we we can return.
-        let mut allowed = &self.allowed;
-        for name in action.name().0 {
-            allowed = match allowed {
-                AllowedActions::None => return false,
-                AllowedActions::All => return true,
-                AllowedActions::Some(actions) => {
-                    if let Some(children_allowed) = actions.get(name.as_ref()) {
-                        children_allowed
-                    } else {
-                        return false;
-                    }
-                }
-            };
+    /// Returns the actions denied on this node, including actions denied by
+    /// statements with conditions or validity periods.
+    fn possibly_denied(&self) -> AllowedActions {
+        let mut denied = self.denied.clone();
+        for statement in &self.statements {
+            if let (Effect::Deny, Some(actions)) = (statement.effect, &statement.actions) {
+                denied.add_actions(actions);
+            }
         }
-        matches!(allowed, AllowedActions::All)
+        denied
     }
 
-    #[must_use]
-    pub fn get<'a: 's, 's, R: AsRef<[Identifier<'a>]>>(
+    fn any_matching<F: Fn(&Self) -> bool>(
+        &self,
+        resource_name: &[Identifier<'_>],
+        context: &Context,
+        matching: Matching,
+        predicate: &F,
+    ) -> bool {
+        self.find_matching(
+            resource_name,
+            context,
+            matching,
+            &mut Vec::new(),
+            &mut |data, _| {
+                if predicate(data) {
+                    Some(())
+                } else {
+                    None
+                }
+            },
+        )
+        .is_some()
+    }
+
+    fn find_matching<
+        'a: 's,
+        's: 'p,
+        'p,
+        T,
+        F: FnMut(&'s Self, &[&'p Identifier<'a>]) -> Option<T>,
+    >(
         &'s self,
-        resource_name: R,
-        key: &str,
-    ) -> Option<&'s Configuration> {
-        let resource_name = resource_name.as_ref();
+        resource_name: &'p [Identifier<'a>],
+        context: &Context,
+        matching: Matching,
+        path: &mut Vec<&'p Identifier<'a>>,
+        matcher: &mut F,
+    ) -> Option<T> {
         // This function checks all possible matches of `resource_name` by using
         // recursion to call itself for each entry in `resource_name`. This
-        // first block does the function call recursion. The second block checks
-        // `action`.
-        if let Some(resource) = resource_name.as_ref().first() {
-            if let Some(children) = &self.children {
-                let remaining_resource = &resource_name[1..resource_name.len()];
-                // Check if there are entries for this resource segment.
-                if let Some(permissions) = children.get(resource) {
-                    if let Some(config) = permissions.get(remaining_resource, key) {
-                        return Some(config);
-                    }
+        // first block does the function call recursion. The final line
+        // evaluates `matcher` against this node.
+        if let Some(resource) = resource_name.first() {
+            let remaining_resource = &resource_name[1..resource_name.len()];
+            // Check if there are entries for this resource segment, then
+            // check if there are entries for `Any`, and finally check any
+            // patterns or variables that match this resource segment.
+            let exact = self
+                .children_equal_to(resource)
+                .iter()
+                .filter(|(identifier, _)| matching.matches(identifier, resource))
+                .map(|(_, permissions)| (resource, permissions));
+            // `Any` matches exactly one segment, so it can't match a
+            // request for `Recursive`, which may be any number of segments.
+            let any = if matches!(resource, Identifier::Recursive) {
+                None
+            } else {
+                self.child(&Identifier::Any)
+            }
+            .map(|permissions| (&Identifier::Any, permissions));
+            let patterns = self
+                .patterns
+                .iter()
+                .filter(|pattern| pattern.matches_in(resource, context, matching.strict))
+                .filter_map(|pattern| {
+                    self.child(pattern)
+                        .map(|permissions| (pattern, permissions))
+                });
+            for (name, permissions) in exact.chain(any).chain(patterns) {
+                path.push(name);
+                let result =
+                    permissions.find_matching(remaining_resource, context, matching, path, matcher);
+                path.pop();
+                if result.is_some() {
+                    return result;
                 }
+            }
+        }
 
-                // Check if there are entries for `Any`.
-                if let Some(permissions) = children.get(&Identifier::Any) {
-                    if let Some(config) = permissions.get(remaining_resource, key) {
-                        return Some(config);
-                    }
+        // `Recursive` matches zero or more segments, so it is checked against
+        // every remaining suffix of `resource_name`, including an empty one.
+        if let Some(permissions) = self.child(&Identifier::Recursive) {
+            path.push(&Identifier::Recursive);
+            for skipped in 0..=resource_name.len() {
+                let result = permissions.find_matching(
+                    &resource_name[skipped..],
+                    context,
+                    matching,
+                    path,
+                    matcher,
+                );
+                if result.is_some() {
+                    path.pop();
+                    return result;
                 }
             }
+            path.pop();
         }
 
-        self.configuration
-            .as_ref()
-            .and_then(|configs| configs.get(key))
+        // When execution reaches here, either resource_name is empty, or none
+        // of the previous paths have matched.
+        matcher(self, path)
+    }
+
+    fn effective_actions(
+        &self,
+        resource_name: &[Identifier<'_>],
+        matching: Matching,
+    ) -> AllowedActions {
+        let (mut allowed, denied) = self.matching_actions(resource_name, matching);
+        allowed.remove_denied(&denied);
+        allowed
+    }
+
+    /// Returns the actions allowed and denied, without conditions, on every
+    /// node matching `resource_name`.
+    fn matching_actions(
+        &self,
+        resource_name: &[Identifier<'_>],
+        matching: Matching,
+    ) -> (AllowedActions, AllowedActions) {
+        let mut allowed = AllowedActions::None;
+        let mut denied = AllowedActions::None;
+        let context = Context::default();
+        self.find_matching::<(), _>(
+            resource_name,
+            &context,
+            matching,
+            &mut Vec::new(),
+            &mut |data, _| {
+                allowed.add_allowed(&data.allowed);
+                denied.add_allowed(&data.denied);
+                None
+            },
+        );
+        (allowed, denied)
+    }
+
+    fn resources_allowing(
+        &self,
+        action: &ActionName,
+        matching: Matching,
+    ) -> Vec<ResourceName<'static>> {
+        let mut resources = Vec::new();
+        self.collect_resources_allowing(action, &mut Vec::new(), &mut resources);
+        resources.retain(|resource| {
+            !self.any_matching(resource.as_ref(), &Context::default(), matching, &|data| {
+                data.denied.allows(action)
+            })
+        });
+        resources
+    }
+
+    /// Invokes `visitor` for this node and every node beneath it.
+    fn visit<F: FnMut(&[Identifier<'static>], &Self)>(
+        &self,
+        path: &mut Vec<Identifier<'static>>,
+        visitor: &mut F,
+    ) {
+        visitor(path, self);
+        if let Some(children) = &self.children {
+            for (name, permissions) in children.values().flatten() {
+                path.push(name.clone());
+                permissions.visit(path, visitor);
+                path.pop();
+            }
+        }
+    }
+
+    fn collect_resources_allowing(
+        &self,
+        action: &ActionName,
+        path: &mut Vec<Identifier<'static>>,
+        resources: &mut Vec<ResourceName<'static>>,
+    ) {
+        if self.allowed.allows(action) {
+            resources.push(ResourceName::from(path.clone()));
+        } else if let Some(children) = &self.children {
+            for (name, permissions) in children.values().flatten() {
+                path.push(name.clone());
+                permissions.collect_resources_allowing(action, path, resources);
+                path.pop();
+            }
+        }
+    }
+
+    fn explain(
+        &self,
+        resource_name: &[Identifier<'_>],
+        action: &ActionName,
+        context: &Context,
+        matching: Matching,
+    ) -> Decision {
+        let find = |effect: Effect| {
+            self.find_matching(
+                resource_name,
+                context,
+                matching,
+                &mut Vec::new(),
+                &mut |data, path| {
+                    data.matching_statement(effect, action, Some(context)).map(
+                        |(statement, action)| MatchedStatement {
+                            resource: ResourceName::from(
+                                path.iter()
+                                    .map(|identifier| Identifier::to_owned(identifier))
+                                    .collect::<Vec<_>>(),
+                            ),
+                            action,
+                            statement: statement.clone(),
+                        },
+                    )
+                },
+            )
+        };
+
+        if let Some(matched) = find(Effect::Deny) {
+            Decision::Denied(matched)
+        } else if let Some(matched) = find(Effect::Allow) {
+            Decision::Allowed(matched)
+        } else {
+            Decision::NotAllowed
+        }
+    }
+
+    /// Returns the first statement on this node with `effect` that applies to
+    /// `action`, along with the prefix of `action` that the statement lists.
+    /// If `context` is None, statement conditions and validity periods are
+    /// ignored.
+    fn matching_statement(
+        &self,
+        effect: Effect,
+        action: &ActionName,
+        context: Option<&Context>,
+    ) -> Option<(&Statement, ActionName)> {
+        self.statements
+            .iter()
+            .filter(|statement| {
+                statement.effect == effect
+                    && match context {
+                        Some(context) => statement.is_met(context),
+                        None => true,
+                    }
+            })
+            .find_map(|statement| {
+                let prefix = statement.actions.as_ref()?.matching_prefix(action)?;
+                Some((&**statement, prefix))
+            })
+    }
+
+    fn get<'a: 's, 's>(
+        &'s self,
+        resource_name: &[Identifier<'a>],
+        key: &str,
+        context: &Context,
+        matching: Matching,
+    ) -> Option<&'s Configuration> {
+        self.find_matching(
+            resource_name,
+            context,
+            matching,
+            &mut Vec::new(),
+            &mut |data, _| {
+                data.configuration
+                    .as_ref()
+                    .and_then(|configs| configs.get(key))
+            },
+        )
     }
 }
 
@@ -208,47 +1188,24 @@ impl From<Vec<Statement>> for Permissions {
     fn from(statements: Vec<Statement>) -> Self {
         let mut permissions = Data::default();
         for statement in statements {
+            let statement = Arc::new(statement);
             // Apply this statement to all resources
-            for resource in statement.resources {
+            for resource in &statement.resources {
                 let mut current_permissions = &mut permissions;
                 // Look up the permissions for the resource path
-                for name in resource {
-                    let permissions = current_permissions
-                        .children
-                        .get_or_insert_with(HashMap::default);
-                    current_permissions = permissions.entry(name).or_default();
+                for name in resource.as_ref() {
+                    current_permissions = current_permissions.child_mut(name);
                 }
+                current_permissions.statements.push(statement.clone());
 
-                // Apply the "allowed" status to each action in this resource.
-                match &statement.actions {
-                    Some(ActionNameList::List(actions)) =>
-                        for action in actions {
-                            let mut allowed = &mut current_permissions.allowed;
-                            for name in &action.0 {
-                                let action_map = match allowed {
-                                    AllowedActions::All | AllowedActions::None => {
-                                        *allowed = {
-                                            let mut action_map = HashMap::new();
-                                            action_map
-                                                .insert(name.to_string(), AllowedActions::None);
-                                            AllowedActions::Some(action_map)
-                                        };
-                                        if let AllowedActions::Some(action_map) = allowed {
-                                            action_map
-                                        } else {
-                                            unreachable!()
-                                        }
-                                    }
-                                    AllowedActions::Some(action_map) => action_map,
-                                };
-                                allowed = action_map.entry(name.to_string()).or_default();
-                            }
-                            *allowed = AllowedActions::All;
-                        },
-                    Some(ActionNameList::All) => {
-                        current_permissions.allowed = AllowedActions::All;
+                // Apply the "allowed" or "denied" status to each action in this
+                // resource. Statements with conditions or validity periods are
+                // evaluated when checking permissions instead.
+                if let (Some(actions), false) = (&statement.actions, statement.is_conditional()) {
+                    match statement.effect {
+                        Effect::Allow => current_permissions.allowed.add_actions(actions),
+                        Effect::Deny => current_permissions.denied.add_actions(actions),
                     }
-                    None => {}
                 }
 
                 if let Some(incoming_configs) = &statement.configuration {
@@ -265,6 +1222,9 @@ impl From<Vec<Statement>> for Permissions {
         }
         Self {
             data: Arc::new(permissions),
+            alternatives: Vec::new(),
+            intersecting: Vec::new(),
+            strict_identifiers: false,
         }
     }
 }
@@ -283,6 +1243,147 @@ impl Default for AllowedActions {
 }
 
 impl AllowedActions {
+    fn add_actions(&mut self, actions: &ActionNameList) {
+        match actions {
+            ActionNameList::List(actions) =>
+                for action in actions {
+                    let mut allowed = &mut *self;
+                    for name in &action.0 {
+                        let action_map = match allowed {
+                            // A parent name is already allowed entirely.
+                            AllowedActions::All => break,
+                            AllowedActions::None => {
+                                *allowed = {
+                                    let mut action_map = HashMap::new();
+                                    action_map.insert(name.to_string(), AllowedActions::None);
+                                    AllowedActions::Some(action_map)
+                                };
+                                if let AllowedActions::Some(action_map) = allowed {
+                                    action_map
+                                } else {
+                                    unreachable!()
+                                }
+                            }
+                            AllowedActions::Some(action_map) => action_map,
+                        };
+                        allowed = action_map.entry(name.to_string()).or_default();
+                    }
+                    *allowed = AllowedActions::All;
+                },
+            ActionNameList::All => {
+                *self = AllowedActions::All;
+            }
+        }
+    }
+
+    /// Returns true if `action` is allowed by this tree. At any stage if we
+    /// reach a match (positive or negative), we can return.
+    fn allows(&self, action: &ActionName) -> bool {
+        self.allows_segments(&action.0)
+    }
+
+    fn allows_segments(&self, names: &[Cow<'static, str>]) -> bool {
+        match self {
+            AllowedActions::None => false,
+            AllowedActions::All => true,
+            AllowedActions::Some(actions) => {
+                if let Some((name, remaining)) = names.split_first() {
+                    // Both the exact name and a wildcard in its place could
+                    // allow the remaining names.
+                    let allows_remaining = |name: &str| matches!(actions.get(name), Some(allowed) if allowed.allows_segments(remaining));
+                    allows_remaining(name) || allows_remaining(ActionName::WILDCARD)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Returns the actions allowed beneath `name`, including those allowed
+    /// beneath a wildcard.
+    fn matching_child(actions: &HashMap<String, Self>, name: &str) -> Self {
+        let mut allowed = AllowedActions::None;
+        if let Some(exact) = actions.get(name) {
+            allowed.add_allowed(exact);
+        }
+        if name != ActionName::WILDCARD {
+            if let Some(wildcard) = actions.get(ActionName::WILDCARD) {
+                allowed.add_allowed(wildcard);
+            }
+        }
+        allowed
+    }
+
+    /// Removes all actions that aren't also allowed by `other`.
+    fn retain_allowed(&mut self, other: &Self) {
+        match (&mut *self, other) {
+            (_, Self::All) | (Self::None, _) => {}
+            (_, Self::None) => *self = Self::None,
+            (Self::All, Self::Some(_)) => *self = other.clone(),
+            (Self::Some(actions), Self::Some(other)) => {
+                actions.retain(|name, allowed| {
+                    allowed.retain_allowed(&Self::matching_child(other, name));
+                    !matches!(allowed, Self::None)
+                });
+                if actions.is_empty() {
+                    *self = Self::None;
+                }
+            }
+        }
+    }
+
+    /// Removes all actions that are entirely denied by `denied`.
+    fn remove_denied(&mut self, denied: &Self) {
+        match (&mut *self, denied) {
+            // A more specific denial can't be represented beneath an `All`.
+            (_, Self::None) | (Self::None | Self::All, Self::Some(_)) => {}
+            (_, Self::All) => *self = Self::None,
+            (Self::Some(actions), Self::Some(denied)) => {
+                actions.retain(|name, allowed| {
+                    allowed.remove_denied(&Self::matching_child(denied, name));
+                    !matches!(allowed, Self::None)
+                });
+                if actions.is_empty() {
+                    *self = Self::None;
+                }
+            }
+        }
+    }
+
+    fn to_action_names(&self) -> ActionNameList {
+        if matches!(self, Self::All) {
+            ActionNameList::All
+        } else {
+            ActionNameList::List(self.names())
+        }
+    }
+
+    /// Returns the sorted list of names that are entirely allowed. If all
+    /// actions are allowed, a single empty name is returned.
+    fn names(&self) -> Vec<ActionName> {
+        fn collect(
+            allowed: &AllowedActions,
+            prefix: &mut Vec<Cow<'static, str>>,
+            names: &mut Vec<ActionName>,
+        ) {
+            match allowed {
+                AllowedActions::None => {}
+                AllowedActions::All => names.push(ActionName(prefix.clone())),
+                AllowedActions::Some(actions) =>
+                    for (name, allowed) in actions {
+                        prefix.push(Cow::Owned(name.clone()));
+                        collect(allowed, prefix, names);
+                        prefix.pop();
+                    },
+            }
+        }
+
+        let mut names = Vec::new();
+        collect(self, &mut Vec::new(), &mut names);
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        names
+    }
+
     fn add_allowed(&mut self, other: &Self) {
         match other {
             Self::None => {}
@@ -303,3 +1404,49 @@ impl AllowedActions {
         }
     }
 }
+
+/// The result of [`Permissions::explain()`].
+#[derive(Debug, Clone)]
+pub enum Decision {
+    /// The action is allowed by the matched statement.
+    Allowed(MatchedStatement),
+    /// The action is denied by the matched statement, which has an
+    /// [`Effect::Deny`].
+    Denied(MatchedStatement),
+    /// No statement allows the action.
+    NotAllowed,
+}
+
+impl Decision {
+    /// Returns true if this decision allows the action.
+    #[must_use]
+    pub const fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed(_))
+    }
+
+    /// Returns the statement that produced this decision, if any.
+    #[must_use]
+    pub const fn matched(&self) -> Option<&MatchedStatement> {
+        match self {
+            Self::Allowed(matched) | Self::Denied(matched) => Some(matched),
+            Self::NotAllowed => None,
+        }
+    }
+}
+
+/// A [`Statement`] that matched while evaluating [`Permissions::explain()`].
+#[derive(Debug, Clone)]
+pub struct MatchedStatement {
+    /// The resource path that matched, as written in the statement. Each
+    /// segment is either the literal segment requested or the identifier that
+    /// matched it: [`Identifier::Any`], [`Identifier::Recursive`], or an
+    /// [`Identifier::Pattern`], [`Identifier::Variable`] or
+    /// [`Identifier::Range`]. A [`Identifier::Recursive`] segment may match
+    /// any number of requested segments.
+    pub resource: ResourceName<'static>,
+    /// The prefix of the requested action that the statement lists. This is
+    /// empty when the statement applies to [`ActionNameList::All`].
+    pub action: ActionName,
+    /// The statement that matched.
+    pub statement: Statement,
+}
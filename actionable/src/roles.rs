@@ -0,0 +1,180 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Permissions, Statement};
+
+/// A named set of statements that can inherit the statements of other roles.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[must_use]
+pub struct Role {
+    /// The statements granted by this role.
+    pub statements: Vec<Statement>,
+    /// The names of the roles whose statements this role also grants.
+    #[serde(default)]
+    pub inherits: Vec<String>,
+}
+
+impl Role {
+    /// Returns a role granting `statements`.
+    pub fn new(statements: Vec<Statement>) -> Self {
+        Self {
+            statements,
+            inherits: Vec::new(),
+        }
+    }
+
+    /// Inherits the statements of the role named `name` and returns self.
+    pub fn inheriting<S: Into<String>>(mut self, name: S) -> Self {
+        self.inherits.push(name.into());
+        self
+    }
+}
+
+/// A collection of named [`Role`]s that resolves lists of role names into
+/// [`Permissions`].
+///
+/// Resolved permissions are cached until any role is defined or removed.
+#[derive(Default, Debug)]
+pub struct RoleRegistry {
+    roles: HashMap<String, Role>,
+    cache: Mutex<HashMap<Vec<String>, Permissions>>,
+}
+
+impl RoleRegistry {
+    /// Defines the role named `name`, replacing any existing definition.
+    ///
+    /// Roles may inherit from roles that haven't been defined yet, but
+    /// resolving permissions will fail until they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RoleError::Cycle`] if `role` inherits from itself, either
+    /// directly or through other roles. The registry is unchanged when an
+    /// error is returned.
+    pub fn define<S: Into<String>>(&mut self, name: S, role: Role) -> Result<(), RoleError> {
+        let name = name.into();
+        let previous = self.roles.insert(name.clone(), role);
+        let mut path = Vec::new();
+        if self.path_to(&name, &name, &mut path, &mut HashSet::new()) {
+            match previous {
+                Some(previous) => self.roles.insert(name.clone(), previous),
+                None => self.roles.remove(&name),
+            };
+            path.insert(0, name);
+            return Err(RoleError::Cycle(path));
+        }
+
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Removes the role named `name`, returning its definition if it was
+    /// present.
+    pub fn remove(&mut self, name: &str) -> Option<Role> {
+        let removed = self.roles.remove(name);
+        self.invalidate();
+        removed
+    }
+
+    /// Returns the role named `name`, if defined.
+    #[must_use]
+    pub fn role(&self, name: &str) -> Option<&Role> {
+        self.roles.get(name)
+    }
+
+    /// Returns the permissions granted by all of `roles` and the roles they
+    /// inherit from.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RoleError::UnknownRole`] if any role in `roles`, or any role
+    /// they inherit from, isn't defined.
+    pub fn permissions_for<S: AsRef<str>>(&self, roles: &[S]) -> Result<Permissions, RoleError> {
+        let mut key = roles
+            .iter()
+            .map(|role| role.as_ref().to_string())
+            .collect::<Vec<_>>();
+        key.sort();
+        key.dedup();
+
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(permissions) = cache.get(&key) {
+            return Ok(permissions.clone());
+        }
+
+        let mut statements = Vec::new();
+        let mut visited = HashSet::new();
+        for name in &key {
+            self.collect_statements(name, &mut visited, &mut statements)?;
+        }
+        let permissions = Permissions::from(statements);
+        cache.insert(key, permissions.clone());
+        Ok(permissions)
+    }
+
+    fn collect_statements<'a>(
+        &'a self,
+        name: &'a str,
+        visited: &mut HashSet<&'a str>,
+        statements: &mut Vec<Statement>,
+    ) -> Result<(), RoleError> {
+        if visited.insert(name) {
+            let role = self
+                .roles
+                .get(name)
+                .ok_or_else(|| RoleError::UnknownRole(name.to_string()))?;
+            statements.extend(role.statements.iter().cloned());
+            for parent in &role.inherits {
+                self.collect_statements(parent, visited, statements)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if `target` can be reached by following the inherited
+    /// roles of `from`. `path` contains the roles followed to reach `target`.
+    fn path_to(
+        &self,
+        from: &str,
+        target: &str,
+        path: &mut Vec<String>,
+        visited: &mut HashSet<String>,
+    ) -> bool {
+        if let Some(role) = self.roles.get(from) {
+            for parent in &role.inherits {
+                path.push(parent.clone());
+                if parent == target
+                    || (visited.insert(parent.clone())
+                        && self.path_to(parent, target, path, visited))
+                {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+
+    fn invalidate(&mut self) {
+        self.cache
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// An error from a [`RoleRegistry`].
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+pub enum RoleError {
+    /// A role isn't defined.
+    #[error("role '{0}' is not defined")]
+    UnknownRole(String),
+    /// A role inherits from itself. Contains the names of the roles in the
+    /// cycle, starting and ending with the same role.
+    #[error("role inherits from itself: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
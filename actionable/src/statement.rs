@@ -4,11 +4,18 @@ use std::{
     convert::TryFrom,
     fmt::{Display, Formatter, Write},
     hash::Hash,
+    marker::PhantomData,
+    ops::Range,
+    str::FromStr,
+    time::SystemTime,
 };
 
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
-use super::{Action, ActionName};
+use super::{Action, ActionName, Condition, Context};
 
 /// A statement of permissions. A statement describes whether one or more
 /// `actions` should be `allowed` to be taken against `resources`.
@@ -19,6 +26,20 @@ pub struct Statement {
     pub resources: Vec<ResourceName<'static>>,
     /// The list of actions this statement applies to.
     pub actions: Option<ActionNameList>,
+    /// Whether `actions` are allowed or denied.
+    #[serde(default)]
+    pub effect: Effect,
+    /// A condition that must be met for `actions` to be allowed or denied.
+    /// Conditions are evaluated against the [`Context`](crate::Context) of
+    /// each request, and do not affect `configuration`.
+    #[serde(default)]
+    pub condition: Option<Condition>,
+    /// If present, `actions` are only allowed or denied at or after this time.
+    #[serde(default)]
+    pub valid_from: Option<SystemTime>,
+    /// If present, `actions` are only allowed or denied before this time.
+    #[serde(default)]
+    pub valid_until: Option<SystemTime>,
     /// Any configured values for these resources.
     pub configuration: Option<HashMap<String, Configuration>>,
 }
@@ -35,6 +56,10 @@ impl Statement {
         Self {
             resources: vec![name.into()],
             actions: None,
+            effect: Effect::Allow,
+            condition: None,
+            valid_from: None,
+            valid_until: None,
             configuration: None,
         }
     }
@@ -44,6 +69,10 @@ impl Statement {
         Self {
             resources: vec![ResourceName::any()],
             actions: None,
+            effect: Effect::Allow,
+            condition: None,
+            valid_from: None,
+            valid_until: None,
             configuration: None,
         }
     }
@@ -53,40 +82,175 @@ impl Statement {
         Self {
             resources: names.into_iter().collect(),
             actions: None,
+            effect: Effect::Allow,
+            condition: None,
+            valid_from: None,
+            valid_until: None,
             configuration: None,
         }
     }
 
     /// Allows `action` to be performed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this statement already denies actions. A statement's
+    /// [`effect`](Self::effect) applies to all of its actions, so allowed and
+    /// denied actions must be in separate statements.
     pub fn allow<A: Action>(&mut self, action: &A) {
-        match &mut self.actions {
-            Some(ActionNameList::All) => {}
-            Some(ActionNameList::List(names)) => {
-                names.push(action.name());
-            }
-            None => {
-                self.actions = Some(ActionNameList::List(vec![action.name()]));
-            }
-        }
+        self.set_effect(Effect::Allow);
+        self.push_action(action);
     }
 
     /// Allows `action` and returns self.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this statement already denies actions.
     pub fn allowing<A: Action>(mut self, action: &A) -> Self {
         self.allow(action);
         self
     }
 
     /// Allows [`ActionNameList::All`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this statement already denies actions.
     pub fn allow_all(&mut self) {
+        self.set_effect(Effect::Allow);
         self.actions = Some(ActionNameList::All);
     }
 
     /// Allows [`ActionNameList::All`] and returns self.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this statement already denies actions.
     pub fn allowing_all(mut self) -> Self {
         self.allow_all();
         self
     }
 
+    /// Denies `action` to be performed. This changes this statement's
+    /// [`effect`](Self::effect) to [`Effect::Deny`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this statement already allows actions. A statement's
+    /// [`effect`](Self::effect) applies to all of its actions, so allowed and
+    /// denied actions must be in separate statements.
+    pub fn deny<A: Action>(&mut self, action: &A) {
+        self.set_effect(Effect::Deny);
+        self.push_action(action);
+    }
+
+    /// Denies `action` and returns self.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this statement already allows actions.
+    pub fn denying<A: Action>(mut self, action: &A) -> Self {
+        self.deny(action);
+        self
+    }
+
+    /// Denies [`ActionNameList::All`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this statement already allows actions.
+    pub fn deny_all(&mut self) {
+        self.set_effect(Effect::Deny);
+        self.actions = Some(ActionNameList::All);
+    }
+
+    /// Denies [`ActionNameList::All`] and returns self.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this statement already allows actions.
+    pub fn denying_all(mut self) -> Self {
+        self.deny_all();
+        self
+    }
+
+    /// Sets this statement's effect before adding an action, panicking if
+    /// actions with the other effect have already been added.
+    fn set_effect(&mut self, effect: Effect) {
+        assert!(
+            self.actions.is_none() || self.effect == effect,
+            "a statement can't both allow and deny actions, use a separate statement instead"
+        );
+        self.effect = effect;
+    }
+
+    fn push_action<A: Action>(&mut self, action: &A) {
+        match &mut self.actions {
+            Some(ActionNameList::All) => {}
+            Some(ActionNameList::List(names)) => {
+                names.push(action.name());
+            }
+            None => {
+                self.actions = Some(ActionNameList::List(vec![action.name()]));
+            }
+        }
+    }
+
+    /// Requires `condition` to be met for this statement's actions to apply.
+    /// If a condition is already present, both conditions must be met.
+    pub fn when(mut self, condition: Condition) -> Self {
+        self.condition = Some(match self.condition.take() {
+            Some(existing) => existing.and(condition),
+            None => condition,
+        });
+        self
+    }
+
+    /// Sets [`valid_from`](Self::valid_from) and returns self. This
+    /// statement's actions will not apply until `time`.
+    pub fn not_before(mut self, time: SystemTime) -> Self {
+        self.valid_from = Some(time);
+        self
+    }
+
+    /// Sets [`valid_until`](Self::valid_until) and returns self. This
+    /// statement's actions will no longer apply once `time` is reached.
+    pub fn expiring_at(mut self, time: SystemTime) -> Self {
+        self.valid_until = Some(time);
+        self
+    }
+
+    /// Returns true if this statement has a condition or is only valid for a
+    /// period of time.
+    pub(crate) const fn is_conditional(&self) -> bool {
+        self.condition.is_some() || self.valid_from.is_some() || self.valid_until.is_some()
+    }
+
+    /// Returns true if this statement has the same condition and validity
+    /// period as `other`.
+    pub(crate) fn has_same_conditions(&self, other: &Self) -> bool {
+        self.condition == other.condition
+            && self.valid_from == other.valid_from
+            && self.valid_until == other.valid_until
+    }
+
+    /// Returns true if this statement's condition and validity period are met
+    /// by `context`.
+    pub(crate) fn is_met(&self, context: &Context) -> bool {
+        let now = context.time();
+        if matches!(self.valid_from, Some(valid_from) if now < valid_from)
+            || matches!(self.valid_until, Some(valid_until) if now >= valid_until)
+        {
+            return false;
+        }
+
+        match &self.condition {
+            Some(condition) => condition.evaluate(context),
+            None => true,
+        }
+    }
+
     /// Sets `configuration` for `key` for the resources in this statement.
     pub fn configure<S: Into<String>, C: Into<Configuration>>(&mut self, key: S, configuration: C) {
         let configurations = self.configuration.get_or_insert_with(HashMap::default);
@@ -104,6 +268,21 @@ impl Statement {
     }
 }
 
+/// The effect a [`Statement`] has on its actions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    /// The actions are allowed, unless another statement denies them.
+    Allow,
+    /// The actions are denied, regardless of any statements that allow them.
+    Deny,
+}
+
+impl Default for Effect {
+    fn default() -> Self {
+        Self::Allow
+    }
+}
+
 /// A single element of a [`ResourceName`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Identifier<'a> {
@@ -116,6 +295,62 @@ pub enum Identifier<'a> {
     String(Cow<'a, str>),
     /// A binary identifier.
     Bytes(Cow<'a, [u8]>),
+    // New variants must be added after this point. Formats such as bincode
+    // serialize variants by index, so reordering them breaks stored data.
+    /// When checking for allowed permissions, allow any match where the
+    /// identifier matches this pattern. A `*` in the pattern matches zero or
+    /// more bytes, and all other bytes must match exactly. For example,
+    /// `tenant-*` matches `tenant-a` and `tenant-`, but not `tenants`.
+    ///
+    /// Patterns are matched against the same byte representation that is used
+    /// to compare identifiers for equality: [`Identifier::Integer`] is matched
+    /// using `u64::to_be_bytes()`. A pattern is only equal to another pattern
+    /// with the same contents, and never matches [`Identifier::Any`] or another
+    /// pattern.
+    ///
+    /// Patterns should be created using [`Identifier::pattern()`]. A pattern
+    /// without a `*` is displayed as the string it matches, so it parses as an
+    /// [`Identifier::String`].
+    Pattern(Cow<'a, str>),
+    /// When checking for allowed permissions, allow any match where this
+    /// identifier is used, for zero or more segments. For example,
+    /// `projects.**.files` matches `projects.files`, `projects.5.files` and
+    /// `projects.5.archive.files`.
+    ///
+    /// Unlike [`Identifier::Any`], which must match exactly one segment,
+    /// `projects.**` also matches `projects`.
+    Recursive,
+    /// When checking for allowed permissions, this identifier is replaced by
+    /// the value of this key in the [`Context`] of the request. For example,
+    /// `users.${principal.id}` matches `users.42` when the context contains
+    /// `principal.id` with a value of `42`. If the key isn't present, nothing
+    /// is matched.
+    ///
+    /// A variable is only equal to another variable with the same key.
+    Variable(Cow<'a, str>),
+    /// A signed integer identifier. A non-negative value is compared against
+    /// other identifiers like the [`Identifier::Integer`] with the same value,
+    /// so `Signed(42)` is equal to `Integer(42)`. A negative value is only
+    /// equal to an `Identifier::Signed` with the same value, and is never
+    /// matched by an [`Identifier::Pattern`].
+    Signed(i64),
+    /// A UUID identifier. This is compared against other identifiers using its
+    /// 16 bytes, so it is equal to an [`Identifier::Bytes`] containing the
+    /// same bytes.
+    Uuid([u8; 16]),
+    /// An identifier made up of multiple identifiers, such as a composite
+    /// primary key. A composite is only equal to another composite whose
+    /// identifiers are equal, in the same order. Its identifiers are compared
+    /// for equality, so [`Identifier::Any`] within a composite only equals
+    /// [`Identifier::Any`].
+    Composite(Vec<Identifier<'a>>),
+    /// When checking for allowed permissions, allow any match where the
+    /// identifier is an [`Identifier::Integer`] within this range. The start
+    /// of the range is inclusive and the end is exclusive, so `0..64` matches
+    /// `0` through `63`. For example, `shards.[0..64]` matches `shards.42`.
+    ///
+    /// A range is only equal to another range with the same bounds.
+    Range(Range<u64>),
 }
 
 impl<'a> Hash for Identifier<'a> {
@@ -138,6 +373,42 @@ impl<'a> Hash for Identifier<'a> {
             Identifier::Bytes(bytes) => {
                 state.write(bytes);
             }
+            Identifier::Signed(int) if *int >= 0 => {
+                state.write(&int.to_be_bytes());
+            }
+            Identifier::Uuid(uuid) => {
+                state.write(uuid);
+            }
+            // The remaining identifiers, including negative signed integers,
+            // are only equal to identifiers of the same variant. Like `Any`,
+            // their hashes only need to be self consistent, so each variant
+            // writes a distinct tag first.
+            Identifier::Recursive => {
+                state.write_u8(1);
+            }
+            Identifier::Pattern(pattern) => {
+                state.write_u8(2);
+                state.write(pattern.as_bytes());
+            }
+            Identifier::Variable(key) => {
+                state.write_u8(3);
+                state.write(key.as_bytes());
+            }
+            Identifier::Composite(identifiers) => {
+                state.write_u8(4);
+                state.write_usize(identifiers.len());
+                for identifier in identifiers {
+                    identifier.hash(state);
+                }
+            }
+            Identifier::Range(range) => {
+                state.write_u8(5);
+                range.hash(state);
+            }
+            Identifier::Signed(int) => {
+                state.write_u8(6);
+                state.write(&int.to_be_bytes());
+            }
         }
     }
 }
@@ -163,6 +434,25 @@ fn identifier_hash_tests() {
     assert_ne!(hash(&string_a), hash(&string_b));
     assert_ne!(hash(&integer_a), hash(&string_b));
     assert_ne!(hash(&bytes_a), hash(&string_b));
+
+    let uuid = [0xab; 16];
+    assert_eq!(
+        hash(&Identifier::signed(42)),
+        hash(&Identifier::from(42_u64))
+    );
+    assert_eq!(hash(&Identifier::uuid(uuid)), hash(&Identifier::from(uuid)));
+    assert_eq!(hash(&Identifier::signed(-1)), hash(&Identifier::signed(-1)));
+    assert_ne!(
+        hash(&Identifier::signed(-1)),
+        hash(&Identifier::from(u64::MAX))
+    );
+    assert_eq!(
+        hash(&Identifier::from((1_u64, "a"))),
+        hash(&Identifier::from((
+            Identifier::from(b"\0\0\0\0\0\0\0\x01"),
+            "a"
+        )))
+    );
 }
 
 impl<'a> Eq for Identifier<'a> {}
@@ -171,9 +461,20 @@ impl<'a> PartialEq for Identifier<'a> {
     fn eq(&self, other: &Self) -> bool {
         match other {
             Self::Any => matches!(self, Self::Any),
+            Self::Recursive => matches!(self, Self::Recursive),
+            Self::Pattern(pattern) => matches!(self, Self::Pattern(ours) if ours == pattern),
+            Self::Variable(key) => matches!(self, Self::Variable(ours) if ours == key),
+            Self::Composite(identifiers) =>
+                matches!(self, Self::Composite(ours) if ours == identifiers),
+            Self::Range(range) => matches!(self, Self::Range(ours) if ours == range),
             Self::Integer(int) => self.eq_int(*int),
+            Self::Signed(int) => match u64::try_from(*int) {
+                Ok(int) => self.eq_int(int),
+                Err(_) => matches!(self, Self::Signed(ours) if ours == int),
+            },
             Self::String(string) => self.eq_str(string),
             Self::Bytes(bytes) => self.eq_bytes(bytes),
+            Self::Uuid(uuid) => self.eq_bytes(uuid),
         }
     }
 }
@@ -184,50 +485,341 @@ impl<'a> Identifier<'a> {
     pub fn to_owned(&self) -> Identifier<'static> {
         match self {
             Self::Any => Identifier::Any,
+            Self::Recursive => Identifier::Recursive,
             Self::Integer(value) => Identifier::Integer(*value),
             Self::String(value) => Identifier::String(Cow::Owned(value.to_string())),
             Self::Bytes(value) => Identifier::Bytes(Cow::Owned(value.to_vec())),
+            Self::Pattern(value) => Identifier::Pattern(Cow::Owned(value.to_string())),
+            Self::Variable(key) => Identifier::Variable(Cow::Owned(key.to_string())),
+            Self::Signed(value) => Identifier::Signed(*value),
+            Self::Uuid(value) => Identifier::Uuid(*value),
+            Self::Composite(identifiers) =>
+                Identifier::Composite(identifiers.iter().map(Identifier::to_owned).collect()),
+            Self::Range(range) => Identifier::Range(range.clone()),
+        }
+    }
+
+    /// Returns an [`Identifier::Signed`] for `value`.
+    #[must_use]
+    pub const fn signed(value: i64) -> Self {
+        Self::Signed(value)
+    }
+
+    /// Returns an [`Identifier::Uuid`] for the 16 bytes of `uuid`.
+    #[must_use]
+    pub const fn uuid(uuid: [u8; 16]) -> Self {
+        Self::Uuid(uuid)
+    }
+
+    /// Returns an [`Identifier::Pattern`] for `pattern`. A pattern without a
+    /// `*` only matches identifiers equal to the same string, so it is
+    /// returned as an [`Identifier::String`] instead. This keeps the [text
+    /// format](ResourceName#text-format) unambiguous, as a pattern is written
+    /// with at least one unquoted `*`.
+    #[must_use]
+    pub fn pattern<P: Into<Cow<'a, str>>>(pattern: P) -> Self {
+        let pattern = pattern.into();
+        if pattern.contains('*') {
+            Self::Pattern(pattern)
+        } else {
+            Self::String(pattern)
+        }
+    }
+
+    /// Returns an [`Identifier::Variable`] for the context `key`.
+    #[must_use]
+    pub fn variable<K: Into<Cow<'a, str>>>(key: K) -> Self {
+        Self::Variable(key.into())
+    }
+
+    /// Returns true if this identifier can't be looked up by its hash and must
+    /// instead be checked using [`Identifier::matches_in()`].
+    pub(crate) const fn is_pattern(&self) -> bool {
+        matches!(self, Self::Pattern(_) | Self::Variable(_) | Self::Range(_))
+    }
+
+    /// Returns true if this identifier is equal to `other` and they are the
+    /// same variant. Unlike `==`, an [`Identifier::Integer`] is never equal to
+    /// an [`Identifier::String`] or [`Identifier::Bytes`] with the same byte
+    /// representation.
+    #[must_use]
+    pub fn eq_strict(&self, other: &Identifier<'_>) -> bool {
+        match (self, other) {
+            (Self::Integer(ours), Identifier::Integer(theirs)) => ours == theirs,
+            (Self::String(ours), Identifier::String(theirs)) => ours == theirs,
+            (Self::Bytes(ours), Identifier::Bytes(theirs)) => ours == theirs,
+            (Self::Signed(ours), Identifier::Signed(theirs)) => ours == theirs,
+            (Self::Uuid(ours), Identifier::Uuid(theirs)) => ours == theirs,
+            (Self::Composite(ours), Identifier::Composite(theirs)) =>
+                ours.len() == theirs.len()
+                    && ours
+                        .iter()
+                        .zip(theirs)
+                        .all(|(ours, theirs)| ours.eq_strict(theirs)),
+            (
+                Self::Integer(_)
+                | Self::String(_)
+                | Self::Bytes(_)
+                | Self::Signed(_)
+                | Self::Uuid(_)
+                | Self::Composite(_),
+                _,
+            ) => false,
+            _ => self == other,
+        }
+    }
+
+    /// Returns true if this identifier is a pattern, variable or range that
+    /// matches `requested`. Variables are looked up in `context`. If `strict`
+    /// is true, values are compared using [`Identifier::eq_strict()`], patterns
+    /// only match [`Identifier::String`] and ranges only match
+    /// [`Identifier::Integer`].
+    pub(crate) fn matches_in(
+        &self,
+        requested: &Identifier<'_>,
+        context: &Context,
+        strict: bool,
+    ) -> bool {
+        match self {
+            Self::Variable(key) =>
+                if let Some(value) = context.get(key) {
+                    let value = Identifier::from(value);
+                    !requested.is_pattern()
+                        && if strict {
+                            value.eq_strict(requested)
+                        } else {
+                            value == *requested
+                        }
+                } else {
+                    false
+                },
+            Self::Pattern(_) if strict && !matches!(requested, Identifier::String(_)) => false,
+            Self::Range(range) => match requested {
+                Identifier::Integer(int) => range.contains(int),
+                // A non-negative signed integer is equal to the integer with
+                // the same value.
+                Identifier::Signed(int) if !strict =>
+                    matches!(u64::try_from(*int), Ok(int) if range.contains(&int)),
+                _ => false,
+            },
+            _ => self.matches_pattern(requested),
+        }
+    }
+
+    /// Returns true if this identifier is a pattern that matches `requested`.
+    pub(crate) fn matches_pattern(&self, requested: &Identifier<'_>) -> bool {
+        match self {
+            Self::Pattern(pattern) =>
+                if let Some(bytes) = requested.as_bytes() {
+                    glob_matches(pattern.as_bytes(), &bytes)
+                } else {
+                    false
+                },
+            _ => false,
+        }
+    }
+
+    /// Returns the byte representation used to compare this identifier for
+    /// equality, or None if this identifier is a wildcard, composite or
+    /// negative signed integer.
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        match self {
+            Self::Any
+            | Self::Recursive
+            | Self::Pattern(_)
+            | Self::Variable(_)
+            | Self::Composite(_)
+            | Self::Range(_) => None,
+            Self::Integer(int) => Some(Cow::Owned(int.to_be_bytes().to_vec())),
+            Self::Signed(int) => u64::try_from(*int)
+                .ok()
+                .map(|int| Cow::Owned(int.to_be_bytes().to_vec())),
+            Self::String(string) => Some(Cow::Borrowed(string.as_bytes())),
+            Self::Bytes(bytes) => Some(Cow::Borrowed(bytes)),
+            Self::Uuid(uuid) => Some(Cow::Borrowed(uuid)),
         }
     }
 
     fn eq_int(&self, other: u64) -> bool {
         match self {
-            Identifier::Any => false,
             Identifier::Integer(int) => *int == other,
-            Identifier::String(string) => {
-                let other = other.to_be_bytes();
-                string.as_bytes() == other
-            }
-            Identifier::Bytes(bytes) => {
-                let other = other.to_be_bytes();
-                **bytes == other
-            }
+            _ => self.eq_bytes(&other.to_be_bytes()),
         }
     }
 
     fn eq_str(&self, other: &str) -> bool {
         match self {
-            Identifier::Any => false,
-            Identifier::Integer(int) => {
-                let int = int.to_be_bytes();
-                int == other.as_bytes()
-            }
             Identifier::String(string) => string == other,
-            Identifier::Bytes(bytes) => &**bytes == other.as_bytes(),
+            _ => self.eq_bytes(other.as_bytes()),
         }
     }
 
     fn eq_bytes(&self, other: &[u8]) -> bool {
         match self {
-            Identifier::Any => false,
+            Identifier::Any
+            | Identifier::Recursive
+            | Identifier::Pattern(_)
+            | Identifier::Variable(_)
+            | Identifier::Composite(_)
+            | Identifier::Range(_) => false,
             Identifier::Integer(int) => {
                 let int = int.to_be_bytes();
                 int == other
             }
+            Identifier::Signed(int) => match u64::try_from(*int) {
+                Ok(int) => int.to_be_bytes() == other,
+                Err(_) => false,
+            },
             Identifier::String(string) => string.as_bytes() == other,
             Identifier::Bytes(bytes) => &**bytes == other,
+            Identifier::Uuid(uuid) => uuid == other,
+        }
+    }
+
+    /// Returns every identifier this identifier is equal to when identifiers
+    /// aren't strict, one for each variant that can hold its byte
+    /// representation. Returns None for patterns, variables and ranges, and
+    /// for composites containing them, as they match identifiers that can't be
+    /// listed.
+    pub(crate) fn loose_equivalents(&self) -> Option<Vec<Identifier<'static>>> {
+        match self {
+            Self::Pattern(_) | Self::Variable(_) | Self::Range(_) => None,
+            Self::Composite(identifiers) => {
+                let equivalents = identifiers
+                    .iter()
+                    .map(Identifier::loose_equivalents)
+                    .collect::<Option<Vec<_>>>()?;
+                Some(
+                    combinations(equivalents)
+                        .into_iter()
+                        .map(Identifier::Composite)
+                        .collect(),
+                )
+            }
+            _ =>
+                if let Some(bytes) = self.as_bytes() {
+                    let mut equivalents = vec![Identifier::Bytes(Cow::Owned(bytes.to_vec()))];
+                    if let Ok(string) = std::str::from_utf8(&bytes) {
+                        equivalents.push(Identifier::String(Cow::Owned(string.to_string())));
+                    }
+                    if let Ok(int) = <[u8; 8]>::try_from(&*bytes) {
+                        let int = u64::from_be_bytes(int);
+                        equivalents.push(Identifier::Integer(int));
+                        if let Ok(int) = i64::try_from(int) {
+                            equivalents.push(Identifier::Signed(int));
+                        }
+                    }
+                    if let Ok(uuid) = <[u8; 16]>::try_from(&*bytes) {
+                        equivalents.push(Identifier::Uuid(uuid));
+                    }
+                    Some(equivalents)
+                } else {
+                    Some(vec![self.to_owned()])
+                },
+        }
+    }
+}
+
+/// Returns every combination of one identifier from each entry of `options`,
+/// in order.
+pub(crate) fn combinations(
+    options: Vec<Vec<Identifier<'static>>>,
+) -> Vec<Vec<Identifier<'static>>> {
+    options
+        .into_iter()
+        .fold(vec![Vec::new()], |combinations, options| {
+            combinations
+                .iter()
+                .flat_map(|combination| {
+                    options.iter().map(move |option| {
+                        let mut combination = combination.clone();
+                        combination.push(option.clone());
+                        combination
+                    })
+                })
+                .collect()
+        })
+}
+
+/// Returns true if `value` matches `pattern`, where `*` in `pattern` matches
+/// zero or more bytes.
+fn glob_matches(pattern: &[u8], value: &[u8]) -> bool {
+    let mut pattern_index = 0;
+    let mut value_index = 0;
+    // The location of the most recent `*` and the value index it is currently
+    // matched up to, allowing backtracking when a later byte doesn't match.
+    let mut last_star = None;
+    while value_index < value.len() {
+        match pattern.get(pattern_index) {
+            Some(b'*') => {
+                last_star = Some((pattern_index, value_index));
+                pattern_index += 1;
+            }
+            Some(byte) if *byte == value[value_index] => {
+                pattern_index += 1;
+                value_index += 1;
+            }
+            _ =>
+                if let Some((star_index, star_value_index)) = last_star {
+                    pattern_index = star_index + 1;
+                    value_index = star_value_index + 1;
+                    last_star = Some((star_index, value_index));
+                } else {
+                    return false;
+                },
         }
     }
+
+    pattern[pattern_index..].iter().all(|byte| *byte == b'*')
+}
+
+#[test]
+fn identifier_pattern_tests() {
+    let pattern = Identifier::pattern("tenant-*");
+    assert!(pattern.matches_pattern(&Identifier::from("tenant-a")));
+    assert!(pattern.matches_pattern(&Identifier::from("tenant-")));
+    assert!(pattern.matches_pattern(&Identifier::from(b"tenant-b")));
+    assert!(!pattern.matches_pattern(&Identifier::from("tenants")));
+    assert!(!pattern.matches_pattern(&Identifier::from("a-tenant-a")));
+    assert!(!pattern.matches_pattern(&Identifier::Any));
+    assert!(!pattern.matches_pattern(&pattern));
+    // Integers are matched using their big endian bytes, consistent with
+    // equality.
+    assert!(Identifier::pattern("hello*")
+        .matches_pattern(&Identifier::from(u64::from_be_bytes(*b"helloooo"))));
+
+    let pattern = Identifier::pattern("*a*b*c");
+    assert!(pattern.matches_pattern(&Identifier::from("abc")));
+    assert!(pattern.matches_pattern(&Identifier::from("xaxxbxxcxc")));
+    assert!(!pattern.matches_pattern(&Identifier::from("xaxxbxxcx")));
+    assert!(Identifier::pattern("*").matches_pattern(&Identifier::from("")));
+
+    // Patterns without a `*` only match the same string.
+    assert!(matches!(Identifier::pattern(""), Identifier::String(empty) if empty.is_empty()));
+    assert!(Identifier::pattern("tenant-a").eq_strict(&Identifier::from("tenant-a")));
+
+    // Patterns are only equal to identical patterns.
+    assert_eq!(
+        Identifier::pattern("tenant-*"),
+        Identifier::pattern("tenant-*")
+    );
+    assert_ne!(
+        Identifier::pattern("tenant-*"),
+        Identifier::from("tenant-*")
+    );
+    assert_ne!(
+        Identifier::from("tenant-*"),
+        Identifier::pattern("tenant-*")
+    );
+    assert_ne!(Identifier::pattern("*"), Identifier::Any);
+    assert_ne!(Identifier::Recursive, Identifier::Any);
+    assert_ne!(Identifier::Any, Identifier::Recursive);
+    assert!(!Identifier::pattern("*").matches_pattern(&Identifier::Recursive));
+
+    // Ranges are only equal to identical ranges.
+    assert_eq!(Identifier::from(0..64), Identifier::from(0..64));
+    assert_ne!(Identifier::from(0..64), Identifier::from(0..63));
+    assert_ne!(Identifier::from(0..1), Identifier::from(0_u64));
 }
 
 #[test]
@@ -271,14 +863,76 @@ fn identifier_equality_tests() {
     assert_ne!(Identifier::Any, integer_a);
     assert_ne!(Identifier::Any, string_a);
     assert_ne!(Identifier::Any, bytes_a);
+
+    // Strict equality also compares variants.
+    assert!(integer_a.eq_strict(&integer_a));
+    assert!(string_a.eq_strict(&string_a));
+    assert!(bytes_a.eq_strict(&bytes_a));
+    assert!(!integer_a.eq_strict(&string_a));
+    assert!(!string_a.eq_strict(&bytes_a));
+    assert!(!bytes_a.eq_strict(&integer_a));
+    assert!(!string_a.eq_strict(&string_b));
+    assert!(Identifier::Any.eq_strict(&Identifier::Any));
+    assert!(!Identifier::Any.eq_strict(&integer_a));
+
+    // Non-negative signed integers are equal to the same unsigned integer,
+    // but negative values are only equal to themselves, even though
+    // `-1_i64.to_be_bytes()` is the same as `u64::MAX.to_be_bytes()`.
+    assert_eq!(Identifier::signed(42), Identifier::from(42_u64));
+    assert_eq!(Identifier::from(42_u64), Identifier::signed(42));
+    assert_eq!(Identifier::signed(-1), Identifier::signed(-1));
+    assert_ne!(Identifier::signed(-1), Identifier::signed(-2));
+    assert_ne!(Identifier::signed(-1), Identifier::from(u64::MAX));
+    assert_ne!(Identifier::from(u64::MAX), Identifier::signed(-1));
+    assert_ne!(Identifier::signed(-1), Identifier::from([0xff; 8]));
+    assert_ne!(Identifier::from([0xff; 8]), Identifier::signed(-1));
+    assert!(!Identifier::signed(42).eq_strict(&Identifier::from(42_u64)));
 }
 
 impl<'a> Display for Identifier<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Any => f.write_char('*'),
+            Self::Recursive => f.write_str("**"),
             Self::Integer(integer) => integer.fmt(f),
-            Self::String(string) => string.fmt(f),
+            Self::Pattern(pattern) => {
+                // Patterns that would otherwise be read as another identifier
+                // start with an empty quoted string.
+                if pattern.is_empty() || *pattern == "*" || *pattern == "**" {
+                    f.write_str("\"\"")?;
+                }
+                for (index, literal) in pattern.split('*').enumerate() {
+                    if index > 0 {
+                        f.write_char('*')?;
+                    }
+                    if literal.chars().any(needs_escape) {
+                        write_quoted(literal, f)?;
+                    } else {
+                        f.write_str(literal)?;
+                    }
+                }
+                Ok(())
+            }
+            Self::Variable(key) => {
+                f.write_str("${")?;
+                for ch in key.chars() {
+                    if matches!(ch, '}' | '\\') {
+                        f.write_char('\\')?;
+                    }
+                    f.write_char(ch)?;
+                }
+                f.write_char('}')
+            }
+            Self::String(string) =>
+                if string.is_empty()
+                    || is_decimal(string.strip_prefix(&['+', '-'][..]).unwrap_or(string))
+                    || parse_uuid(string).is_some()
+                    || string.chars().any(|ch| ch == '*' || needs_escape(ch))
+                {
+                    write_quoted(string, f)
+                } else {
+                    f.write_str(string)
+                },
             Self::Bytes(bytes) => {
                 f.write_char('$')?;
                 for byte in bytes.iter() {
@@ -286,16 +940,88 @@ impl<'a> Display for Identifier<'a> {
                 }
                 Ok(())
             }
+            Self::Signed(int) => write!(f, "{:+}", int),
+            Self::Uuid(uuid) => {
+                for (index, byte) in uuid.iter().enumerate() {
+                    if matches!(index, 4 | 6 | 8 | 10) {
+                        f.write_char('-')?;
+                    }
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            Self::Composite(identifiers) => {
+                f.write_char('(')?;
+                for (index, identifier) in identifiers.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    identifier.fmt(f)?;
+                }
+                f.write_char(')')
+            }
+            Self::Range(range) => write!(f, "[{}..{}]", range.start, range.end),
+        }
+    }
+}
+
+/// Returns true if `ch` must be quoted or escaped within a string or pattern.
+const fn needs_escape(ch: char) -> bool {
+    matches!(ch, '.' | '"' | '\\' | '$' | '(' | ')' | ',' | '[' | ']')
+}
+
+/// Writes `value` surrounded by double quotes, escaping `"` and `\`.
+fn write_quoted(value: &str, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_char('"')?;
+    for ch in value.chars() {
+        if matches!(ch, '"' | '\\') {
+            f.write_char('\\')?;
         }
+        f.write_char(ch)?;
     }
+    f.write_char('"')
 }
 
 #[test]
 fn identifier_display_tests() {
     assert_eq!(Identifier::Any.to_string(), "*");
-    assert_eq!(Identifier::from(1).to_string(), "1");
+    assert_eq!(Identifier::Recursive.to_string(), "**");
+    assert_eq!(Identifier::from(1_u64).to_string(), "1");
     assert_eq!(Identifier::from("string").to_string(), "string");
     assert_eq!(Identifier::from(b"bytes").to_string(), "$6279746573");
+    assert_eq!(
+        Identifier::variable("principal.id").to_string(),
+        "${principal.id}"
+    );
+    assert_eq!(Identifier::from("a.b").to_string(), r#""a.b""#);
+    assert_eq!(Identifier::from("1").to_string(), r#""1""#);
+    assert_eq!(Identifier::from("").to_string(), r#""""#);
+    assert_eq!(
+        Identifier::from(r#"say "hi""#).to_string(),
+        r#""say \"hi\"""#
+    );
+    assert_eq!(Identifier::pattern("tenant-*").to_string(), "tenant-*");
+    assert_eq!(Identifier::pattern("a.b*").to_string(), r#""a.b"*"#);
+    assert_eq!(Identifier::pattern("*").to_string(), r#"""*"#);
+    assert_eq!(Identifier::variable("a}b").to_string(), r"${a\}b}");
+    assert_eq!(Identifier::signed(-5).to_string(), "-5");
+    assert_eq!(Identifier::signed(5).to_string(), "+5");
+    assert_eq!(Identifier::from("-5").to_string(), r#""-5""#);
+    assert_eq!(
+        Identifier::uuid(*b"\x12\x3e\x45\x67\xe8\x9b\x12\xd3\xa4\x56\x42\x66\x14\x17\x40\x00")
+            .to_string(),
+        "123e4567-e89b-12d3-a456-426614174000"
+    );
+    assert_eq!(
+        Identifier::from("123e4567-e89b-12d3-a456-426614174000").to_string(),
+        r#""123e4567-e89b-12d3-a456-426614174000""#
+    );
+    assert_eq!(
+        Identifier::from((42_u64, "a,b", Identifier::Any)).to_string(),
+        r#"(42,"a,b",*)"#
+    );
+    assert_eq!(Identifier::from("(a)").to_string(), r#""(a)""#);
+    assert_eq!(Identifier::from(0..64).to_string(), "[0..64]");
 }
 
 impl<'a> From<u64> for Identifier<'a> {
@@ -304,6 +1030,22 @@ impl<'a> From<u64> for Identifier<'a> {
     }
 }
 
+impl<'a> From<Range<u64>> for Identifier<'a> {
+    fn from(range: Range<u64>) -> Self {
+        Self::Range(range)
+    }
+}
+
+impl<'a> From<&'a Configuration> for Identifier<'a> {
+    fn from(value: &'a Configuration) -> Self {
+        match value {
+            Configuration::Unsigned(unsigned) => Self::Integer(*unsigned),
+            Configuration::Signed(signed) => Self::Signed(*signed),
+            Configuration::String(string) => Self::String(Cow::Borrowed(string)),
+        }
+    }
+}
+
 impl<'a> From<&'a str> for Identifier<'a> {
     fn from(id: &'a str) -> Self {
         Self::String(Cow::Borrowed(id))
@@ -352,9 +1094,36 @@ impl<'a> From<Vec<u8>> for Identifier<'a> {
     }
 }
 
+impl<'a> From<Vec<Identifier<'a>>> for Identifier<'a> {
+    fn from(identifiers: Vec<Identifier<'a>>) -> Self {
+        Self::Composite(identifiers)
+    }
+}
+
+impl<'a, A, B> From<(A, B)> for Identifier<'a>
+where
+    A: Into<Identifier<'a>>,
+    B: Into<Identifier<'a>>,
+{
+    fn from((a, b): (A, B)) -> Self {
+        Self::Composite(vec![a.into(), b.into()])
+    }
+}
+
+impl<'a, A, B, C> From<(A, B, C)> for Identifier<'a>
+where
+    A: Into<Identifier<'a>>,
+    B: Into<Identifier<'a>>,
+    C: Into<Identifier<'a>>,
+{
+    fn from((a, b, c): (A, B, C)) -> Self {
+        Self::Composite(vec![a.into(), b.into(), c.into()])
+    }
+}
+
 #[test]
 fn identifier_from_tests() {
-    assert_eq!(Identifier::from(1).to_string(), "1");
+    assert_eq!(Identifier::from(1_u64).to_string(), "1");
     assert_eq!(Identifier::from("string").to_string(), "string");
     assert_eq!(
         Identifier::from(&String::from("string")).to_string(),
@@ -372,6 +1141,11 @@ fn identifier_from_tests() {
         Identifier::from(&b"bytes".to_vec()).to_string(),
         "$6279746573"
     );
+    assert_eq!(Identifier::from((1_u64, "a")).to_string(), "(1,a)");
+    assert_eq!(
+        Identifier::from(vec![Identifier::from(1_u64)]).to_string(),
+        "(1)"
+    );
 }
 
 /// A list of [`ActionName`]s.
@@ -383,6 +1157,18 @@ pub enum ActionNameList {
     All,
 }
 
+impl ActionNameList {
+    /// Returns the name in this list that is equal to or a prefix of
+    /// `action`, treating [wildcard](ActionName::WILDCARD) segments as
+    /// matching any segment. [`ActionNameList::All`] returns an empty name.
+    pub(crate) fn matching_prefix(&self, action: &ActionName) -> Option<ActionName> {
+        match self {
+            Self::All => Some(ActionName::default()),
+            Self::List(names) => names.iter().find(|name| name.is_prefix_of(action)).cloned(),
+        }
+    }
+}
+
 impl<T> From<T> for ActionNameList
 where
     T: Action,
@@ -402,7 +1188,7 @@ where
 }
 
 /// A configured value for a resource.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Configuration {
     /// An unsigned integer configuration value.
     Unsigned(u64),
@@ -471,7 +1257,42 @@ impl<'a> From<&'a str> for Configuration {
 }
 
 /// A unique name/identifier of a resource.
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+///
+/// ## Text format
+///
+/// Resource names are [displayed](Display) and [parsed](FromStr) as a list of
+/// identifiers separated by `.`, such as `users.42.posts`. Each identifier is
+/// written as:
+///
+/// * `*` for [`Identifier::Any`].
+/// * `**` for [`Identifier::Recursive`].
+/// * The integer in decimal for [`Identifier::Integer`], such as `42`.
+/// * The integer in decimal with a leading `+` or `-` for
+///   [`Identifier::Signed`], such as `+42` or `-42`.
+/// * The hyphenated hexadecimal form for [`Identifier::Uuid`], such as
+///   `123e4567-e89b-12d3-a456-426614174000`.
+/// * Each identifier separated by `,` and surrounded by parentheses for
+///   [`Identifier::Composite`], such as `(42,tenant-a)`.
+/// * The start and end in decimal, separated by `..` and surrounded by square
+///   brackets for [`Identifier::Range`], such as `[0..64]`.
+/// * `$` followed by the bytes in hexadecimal for [`Identifier::Bytes`], such
+///   as `$0aff`.
+/// * `${key}` for [`Identifier::Variable`], where `}` and `\` in the key are
+///   escaped with `\`.
+/// * The pattern with each `*` unquoted for [`Identifier::Pattern`], such as
+///   `tenant-*`.
+/// * The string for [`Identifier::String`], surrounded by double quotes if it
+///   is empty, contains `.`, `*`, `"`, `\`, `$`, `(`, `)`, `,`, `[` or `]`, or
+///   would be read as an integer or UUID. Within quotes, `"` and `\` are
+///   escaped with `\`.
+///
+/// Outside of quotes, `\` escapes the following character, and quoted and
+/// unquoted text can be combined within one identifier. For example,
+/// `"tenant."*` is a pattern matching identifiers starting with `tenant.`.
+/// A pattern without a `*` is parsed as the string it matches.
+///
+/// When serialized, resource names use this text format.
+#[derive(Default, Debug, Clone)]
 pub struct ResourceName<'a>(Vec<Identifier<'a>>);
 
 impl<'a> ResourceName<'a> {
@@ -496,6 +1317,322 @@ impl<'a> Display for ResourceName<'a> {
     }
 }
 
+impl FromStr for ResourceName<'static> {
+    type Err = ResourceNameError;
+
+    /// Parses a name in the [text format](ResourceName#text-format). An empty
+    /// string parses as an empty name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+
+        split_segments(s)?
+            .into_iter()
+            .map(|segment| parse_identifier(segment, s))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl FromStr for Identifier<'static> {
+    type Err = ResourceNameError;
+
+    /// Parses a single identifier in the [text
+    /// format](ResourceName#text-format).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = split_segments(s)?;
+        if segments.len() == 1 {
+            parse_identifier(segments.remove(0), s)
+        } else {
+            Err(ResourceNameError::InvalidSegment {
+                name: s.to_string(),
+                segment: s.to_string(),
+            })
+        }
+    }
+}
+
+/// Splits `text`, which is part of `name`, on each `separator` that isn't
+/// escaped, quoted or within a variable, composite or range.
+fn split_unquoted<'t>(
+    text: &'t str,
+    separator: char,
+    name: &str,
+) -> Result<Vec<&'t str>, ResourceNameError> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut depth = 0_usize;
+    let mut chars = text.char_indices();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            ch if ch == separator && depth == 0 => {
+                segments.push(&text[start..index]);
+                start = index + 1;
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '\\' => {
+                chars
+                    .next()
+                    .ok_or_else(|| ResourceNameError::TrailingEscape {
+                        name: name.to_string(),
+                    })?;
+            }
+            '"' => skip_delimited(&mut chars, '"').ok_or_else(|| {
+                ResourceNameError::UnterminatedQuote {
+                    name: name.to_string(),
+                }
+            })?,
+            '$' if text[index + 1..].starts_with('{') => {
+                skip_delimited(&mut chars, '}').ok_or_else(|| {
+                    ResourceNameError::UnterminatedVariable {
+                        name: name.to_string(),
+                    }
+                })?;
+            }
+            '[' => skip_delimited(&mut chars, ']').ok_or_else(|| {
+                ResourceNameError::UnterminatedRange {
+                    name: name.to_string(),
+                }
+            })?,
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return Err(ResourceNameError::UnterminatedComposite {
+            name: name.to_string(),
+        });
+    }
+    segments.push(&text[start..]);
+    Ok(segments)
+}
+
+/// Splits `name` on each `.` that isn't escaped, quoted or within a variable,
+/// composite or range.
+fn split_segments(name: &str) -> Result<Vec<&str>, ResourceNameError> {
+    split_unquoted(name, '.', name)
+}
+
+/// Advances `chars` past the next `end` that isn't escaped. Returns None if
+/// `end` isn't found.
+fn skip_delimited(chars: &mut std::str::CharIndices<'_>, end: char) -> Option<()> {
+    loop {
+        match chars.next()?.1 {
+            '\\' => {
+                chars.next()?;
+            }
+            ch if ch == end => return Some(()),
+            _ => {}
+        }
+    }
+}
+
+/// Returns true if `text` is non-empty and only contains ASCII digits.
+fn is_decimal(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// Parses a hyphenated UUID, such as `123e4567-e89b-12d3-a456-426614174000`.
+fn parse_uuid(text: &str) -> Option<[u8; 16]> {
+    if text.len() != 36 {
+        return None;
+    }
+
+    let mut uuid = [0; 16];
+    let mut digits = text.char_indices().filter_map(|(index, ch)| {
+        if matches!(index, 8 | 13 | 18 | 23) {
+            // Hyphens are skipped, and anything else is invalid.
+            (ch != '-').then_some(None)
+        } else {
+            Some(ch.to_digit(16))
+        }
+    });
+    for byte in &mut uuid {
+        let high = digits.next()??;
+        let low = digits.next()??;
+        *byte = u8::try_from(high << 4 | low).ok()?;
+    }
+    Some(uuid)
+}
+
+/// Parses a single `segment` of `name`, which has already been checked for
+/// unterminated quotes, variables, composites, ranges and escapes by
+/// [`split_unquoted()`].
+fn parse_identifier(segment: &str, name: &str) -> Result<Identifier<'static>, ResourceNameError> {
+    let invalid_segment = || ResourceNameError::InvalidSegment {
+        name: name.to_string(),
+        segment: segment.to_string(),
+    };
+    match segment {
+        "" => Err(ResourceNameError::EmptySegment {
+            name: name.to_string(),
+        }),
+        "*" => Ok(Identifier::Any),
+        "**" => Ok(Identifier::Recursive),
+        _ if segment.starts_with("${") => {
+            let mut key = String::new();
+            let mut chars = segment[2..].chars();
+            while let Some(ch) = chars.next() {
+                match ch {
+                    '}' =>
+                        return if chars.as_str().is_empty() {
+                            Ok(Identifier::Variable(Cow::Owned(key)))
+                        } else {
+                            Err(invalid_segment())
+                        },
+                    '\\' => key.extend(chars.next()),
+                    ch => key.push(ch),
+                }
+            }
+            Err(invalid_segment())
+        }
+        _ if segment.starts_with('$') => {
+            let digits = segment[1..]
+                .chars()
+                .map(|ch| ch.to_digit(16))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(invalid_segment)?;
+            digits
+                .chunks(2)
+                .map(|pair| match pair {
+                    [high, low] => u8::try_from(high << 4 | low).ok(),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|bytes| Identifier::Bytes(Cow::Owned(bytes)))
+                .ok_or_else(invalid_segment)
+        }
+        _ if segment.starts_with('(') && segment.ends_with(')') => {
+            let contents = &segment[1..segment.len() - 1];
+            if contents.is_empty() {
+                return Ok(Identifier::Composite(Vec::new()));
+            }
+            split_unquoted(contents, ',', name)?
+                .into_iter()
+                .map(|identifier| parse_identifier(identifier, name))
+                .collect::<Result<_, _>>()
+                .map(Identifier::Composite)
+        }
+        _ if segment.starts_with('[') && segment.ends_with(']') => {
+            let (start, end) = segment[1..segment.len() - 1]
+                .split_once("..")
+                .filter(|(start, end)| is_decimal(start) && is_decimal(end))
+                .ok_or_else(invalid_segment)?;
+            match (start.parse(), end.parse()) {
+                (Ok(start), Ok(end)) if start <= end => Ok(Identifier::Range(start..end)),
+                _ => Err(invalid_segment()),
+            }
+        }
+        _ if is_decimal(segment) => segment
+            .parse()
+            .map(Identifier::Integer)
+            .map_err(|_| invalid_segment()),
+        _ if (segment.starts_with('+') || segment.starts_with('-'))
+            && is_decimal(&segment[1..]) =>
+            segment
+                .parse()
+                .map(Identifier::Signed)
+                .map_err(|_| invalid_segment()),
+        _ => Ok(parse_uuid(segment).map_or_else(|| parse_literal(segment), Identifier::Uuid)),
+    }
+}
+
+/// Parses a string or pattern, removing quotes and escapes.
+fn parse_literal(segment: &str) -> Identifier<'static> {
+    let mut value = String::new();
+    let mut is_pattern = false;
+    let mut chars = segment.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => value.extend(chars.next()),
+            '"' =>
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '"' => break,
+                        '\\' => value.extend(chars.next()),
+                        ch => value.push(ch),
+                    }
+                },
+            '*' => {
+                is_pattern = true;
+                value.push(ch);
+            }
+            ch => value.push(ch),
+        }
+    }
+    if is_pattern {
+        Identifier::Pattern(Cow::Owned(value))
+    } else {
+        Identifier::String(Cow::Owned(value))
+    }
+}
+
+impl<'a> Serialize for ResourceName<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for ResourceName<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(ResourceNameVisitor(PhantomData))
+    }
+}
+
+struct ResourceNameVisitor<'a>(PhantomData<ResourceName<'a>>);
+
+impl<'de, 'a> Visitor<'de> for ResourceNameVisitor<'a> {
+    type Value = ResourceName<'a>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("a resource name")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        v.parse::<ResourceName<'static>>().map_err(E::custom)
+    }
+}
+
+/// Deserializes a [`ResourceName`] from either its text format or a list of
+/// identifiers, which is how resource names were previously serialized.
+struct LegacyResourceNameVisitor<'a>(PhantomData<ResourceName<'a>>);
+
+impl<'de, 'a> Visitor<'de> for LegacyResourceNameVisitor<'a> {
+    type Value = ResourceName<'a>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("a resource name or a list of identifiers")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        ResourceNameVisitor(PhantomData).visit_str(v)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut identifiers = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(identifier) = seq.next_element()? {
+            identifiers.push(identifier);
+        }
+        Ok(ResourceName(identifiers))
+    }
+}
+
 impl<'a> ResourceName<'a> {
     /// Creates a `ResourceName` that matches any identifier.
     #[must_use]
@@ -515,6 +1652,247 @@ impl<'a> ResourceName<'a> {
         self.0.push(name.into());
         self
     }
+
+    /// Deserializes a resource name that was serialized either using its
+    /// [text format](ResourceName#text-format) or as a list of identifiers,
+    /// which is how previous versions serialized resource names. This can be
+    /// used as a field's `deserialize_with` function to read stored data.
+    ///
+    /// Because it can't know ahead of time which representation was used,
+    /// this only works with self-describing formats.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is neither a valid resource name string
+    /// nor a list of identifiers.
+    pub fn deserialize_legacy<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(LegacyResourceNameVisitor(PhantomData))
+    }
+}
+
+/// An error parsing a [`ResourceName`] or [`Identifier`] from its [text
+/// format](ResourceName#text-format).
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+pub enum ResourceNameError {
+    /// The name contains an empty segment, such as `users..posts`.
+    #[error("resource name '{name}' contains an empty segment")]
+    EmptySegment {
+        /// The name being parsed.
+        name: String,
+    },
+    /// The name contains a `"` without a matching closing `"`.
+    #[error("resource name '{name}' contains an unterminated quote")]
+    UnterminatedQuote {
+        /// The name being parsed.
+        name: String,
+    },
+    /// The name contains a `${` without a matching closing `}`.
+    #[error("resource name '{name}' contains an unterminated variable")]
+    UnterminatedVariable {
+        /// The name being parsed.
+        name: String,
+    },
+    /// The name contains a `(` without a matching closing `)`.
+    #[error("resource name '{name}' contains an unterminated composite")]
+    UnterminatedComposite {
+        /// The name being parsed.
+        name: String,
+    },
+    /// The name contains a `[` without a matching closing `]`.
+    #[error("resource name '{name}' contains an unterminated range")]
+    UnterminatedRange {
+        /// The name being parsed.
+        name: String,
+    },
+    /// The name ends with a `\` that doesn't escape any character.
+    #[error("resource name '{name}' ends with an escape")]
+    TrailingEscape {
+        /// The name being parsed.
+        name: String,
+    },
+    /// A segment isn't a valid identifier, such as invalid hexadecimal bytes
+    /// or an integer that doesn't fit in a `u64`.
+    #[error("invalid identifier '{segment}' in resource name '{name}'")]
+    InvalidSegment {
+        /// The name being parsed.
+        name: String,
+        /// The invalid segment.
+        segment: String,
+    },
+}
+
+#[test]
+fn resource_name_parse_tests() {
+    fn parse(name: &str) -> Vec<Identifier<'static>> {
+        name.parse::<ResourceName<'_>>().unwrap().0
+    }
+
+    assert_eq!(parse("users.42.posts"), [
+        Identifier::from("users"),
+        Identifier::Integer(42),
+        Identifier::from("posts")
+    ]);
+    assert!(matches!(
+        &parse("*.**.$0aff.${principal.id}.tenant-*")[..],
+        [
+            Identifier::Any,
+            Identifier::Recursive,
+            Identifier::Bytes(bytes),
+            Identifier::Variable(key),
+            Identifier::Pattern(pattern)
+        ] if **bytes == [0x0a, 0xff] && key == "principal.id" && pattern == "tenant-*"
+    ));
+    assert!(matches!(
+        &parse(r#""1".a\.b."x*y""#)[..],
+        [Identifier::String(a), Identifier::String(b), Identifier::String(c)]
+            if a == "1" && b == "a.b" && c == "x*y"
+    ));
+    assert!(parse("").is_empty());
+
+    let error = |name: &str| name.parse::<ResourceName<'_>>().unwrap_err();
+    assert!(matches!(
+        error("a..b"),
+        ResourceNameError::EmptySegment { .. }
+    ));
+    assert!(matches!(
+        error(r#"a."b"#),
+        ResourceNameError::UnterminatedQuote { .. }
+    ));
+    assert!(matches!(
+        error("a.${b"),
+        ResourceNameError::UnterminatedVariable { .. }
+    ));
+    assert!(matches!(
+        error(r"a\"),
+        ResourceNameError::TrailingEscape { .. }
+    ));
+    assert_eq!(error("a.$0g"), ResourceNameError::InvalidSegment {
+        name: String::from("a.$0g"),
+        segment: String::from("$0g"),
+    });
+    assert!(matches!(
+        error("18446744073709551616"),
+        ResourceNameError::InvalidSegment { .. }
+    ));
+    assert!(matches!(
+        error("${a}b"),
+        ResourceNameError::InvalidSegment { .. }
+    ));
+    assert!(matches!(
+        error("-9223372036854775809"),
+        ResourceNameError::InvalidSegment { .. }
+    ));
+    assert!(matches!(
+        error("a.(1,(2)"),
+        ResourceNameError::UnterminatedComposite { .. }
+    ));
+    assert!(matches!(
+        error("(1,,2)"),
+        ResourceNameError::EmptySegment { .. }
+    ));
+    assert!(matches!(
+        error("shards.[0..64"),
+        ResourceNameError::UnterminatedRange { .. }
+    ));
+    for invalid in ["[64..0]", "[0..]", "[-1..5]", "[0..+5]", "[0-5]"] {
+        assert!(matches!(
+            error(invalid),
+            ResourceNameError::InvalidSegment { .. }
+        ));
+    }
+
+    assert!(matches!(
+        "42".parse::<Identifier<'_>>(),
+        Ok(Identifier::Integer(42))
+    ));
+    assert!("a.b".parse::<Identifier<'_>>().is_err());
+}
+
+#[test]
+fn resource_name_round_trip_tests() {
+    // Every identifier round-trips through its text format.
+    let name = ResourceName::from(vec![
+        Identifier::Any,
+        Identifier::Recursive,
+        Identifier::Integer(u64::MAX),
+        Identifier::from(""),
+        Identifier::from("0"),
+        Identifier::from("$ and \\ and \" and . and *"),
+        Identifier::from("${not a variable}"),
+        Identifier::from(b""),
+        Identifier::from(b"\x00bytes"),
+        Identifier::pattern(""),
+        Identifier::pattern("*"),
+        Identifier::pattern("**"),
+        Identifier::pattern("$tenant.\"*\".*"),
+        Identifier::variable("}\\"),
+        Identifier::Signed(i64::MIN),
+        Identifier::Signed(0),
+        Identifier::from("+1"),
+        Identifier::uuid([0xff; 16]),
+        Identifier::from("ffffffff-ffff-ffff-ffff-ffffffffffff"),
+        Identifier::Composite(Vec::new()),
+        Identifier::from((
+            Identifier::variable("a,b.c"),
+            Identifier::pattern("(*)"),
+            (1_u64, "x.y"),
+        )),
+        Identifier::from("(a),b"),
+        Identifier::from(0..u64::MAX),
+        Identifier::from("[0..1]"),
+    ]);
+    let parsed = name.to_string().parse::<ResourceName<'_>>().unwrap();
+    assert_eq!(parsed.to_string(), name.to_string());
+    for (parsed, original) in parsed.0.iter().zip(&name.0) {
+        assert_eq!(parsed, original);
+        assert!(parsed.eq_strict(original));
+        assert_eq!(
+            std::mem::discriminant(parsed),
+            std::mem::discriminant(original)
+        );
+    }
+}
+
+#[test]
+fn resource_name_serde_tests() {
+    use serde::de::{
+        value::{Error, MapAccessDeserializer, MapDeserializer, SeqDeserializer},
+        IntoDeserializer,
+    };
+
+    let name =
+        ResourceName::deserialize(IntoDeserializer::<Error>::into_deserializer("users.${id}"))
+            .unwrap();
+    assert!(matches!(
+        &name.0[..],
+        [Identifier::String(users), Identifier::Variable(id)] if users == "users" && id == "id"
+    ));
+    assert!(
+        ResourceName::deserialize(IntoDeserializer::<Error>::into_deserializer("users..")).is_err()
+    );
+
+    // Lists of identifiers are only accepted through `deserialize_legacy()`.
+    let legacy = || {
+        let identifier = |name: &'static str| {
+            MapAccessDeserializer::new(MapDeserializer::<_, Error>::new(
+                vec![("String", name)].into_iter(),
+            ))
+        };
+        SeqDeserializer::<_, Error>::new(vec![identifier("users"), identifier("42")].into_iter())
+    };
+    assert!(ResourceName::deserialize(legacy()).is_err());
+    let name = ResourceName::deserialize_legacy(legacy()).unwrap();
+    assert!(matches!(
+        &name.0[..],
+        [Identifier::String(users), Identifier::String(id)] if users == "users" && id == "42"
+    ));
+    let name =
+        ResourceName::deserialize_legacy(IntoDeserializer::<Error>::into_deserializer("users.42"))
+            .unwrap();
+    assert_eq!(name.to_string(), "users.42");
 }
 
 impl<'a> AsRef<[Identifier<'a>]> for ResourceName<'a> {
@@ -538,6 +1916,12 @@ impl<'b, 'a> From<&'b [Identifier<'a>]> for ResourceName<'a> {
     }
 }
 
+impl<'a> From<Vec<Identifier<'a>>> for ResourceName<'a> {
+    fn from(parts: Vec<Identifier<'a>>) -> Self {
+        Self(parts)
+    }
+}
+
 impl<'a> From<&'a str> for ResourceName<'a> {
     fn from(name: &'a str) -> Self {
         Self(vec![Identifier::from(name)])
@@ -1,21 +1,26 @@
 #![allow(unused_variables)]
 
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    time::{Duration, SystemTime},
+};
 
 use crate::{
-    Action, ActionName, Actionable, AsyncDispatcher, PermissionDenied, Permissions, ResourceName,
-    Statement,
+    Action, ActionName, ActionNameError, ActionNameList, ActionRegistry, Actionable,
+    AsyncDispatcher, Condition, Configuration, Context, Decision, Effect, Identifier,
+    PermissionDenied, Permissions, ResourceName, Role, RoleError, RoleRegistry, Statement,
 };
 
 #[derive(Debug, Action)]
-#[action(actionable = crate)]
+#[action(actionable = crate, parse)]
 enum TestActions {
     DoSomething,
     Post(PostActions),
 }
 
 #[derive(Debug, Action)]
-#[action(actionable = crate)]
+#[action(actionable = crate, parse)]
 enum PostActions {
     Read,
     Update,
@@ -97,6 +102,310 @@ fn multiple_actions() {
     ));
 }
 
+#[test]
+fn deny_tests() {
+    let permissions = Permissions::from(vec![
+        Statement::allow_all_for_any_resource(),
+        // Deny deleting posts on every resource.
+        Statement::for_any().denying(&TestActions::Post(PostActions::Delete)),
+        // Deny everything on a specific nested resource.
+        Statement::for_resource(ResourceName::named("billing").and("invoices")).denying_all(),
+        // Allowing explicitly does not override a deny.
+        Statement::for_resource(ResourceName::named("billing").and("invoices"))
+            .allowing(&TestActions::DoSomething),
+    ]);
+
+    assert!(permissions.allowed_to(
+        &ResourceName::named("someresource"),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(!permissions.allowed_to(
+        &ResourceName::named("someresource"),
+        &TestActions::Post(PostActions::Delete)
+    ));
+    assert!(!permissions.allowed_to(
+        &ResourceName::named("billing"),
+        &TestActions::Post(PostActions::Delete)
+    ));
+    assert!(permissions.allowed_to(&ResourceName::named("billing"), &TestActions::DoSomething));
+    assert!(!permissions.allowed_to(
+        &ResourceName::named("billing").and("invoices"),
+        &TestActions::DoSomething
+    ));
+    assert!(!permissions.allowed_to(
+        &ResourceName::named("billing").and("invoices").and(42),
+        &TestActions::Post(PostActions::Read)
+    ));
+
+    // Denies are preserved when merging.
+    let merged = Permissions::merged([&permissions, &Permissions::allow_all()]);
+    assert!(!merged.allowed_to(
+        &ResourceName::named("billing").and("invoices"),
+        &TestActions::DoSomething
+    ));
+    assert!(merged.allowed_to(&ResourceName::named("billing"), &TestActions::DoSomething));
+}
+
+#[test]
+fn mixed_effect_tests() {
+    // A statement's effect applies to all of its actions, so mixing allowed
+    // and denied actions is rejected rather than silently denying both.
+    assert!(std::panic::catch_unwind(|| {
+        Statement::for_any()
+            .allowing(&TestActions::Post(PostActions::Read))
+            .denying(&TestActions::Post(PostActions::Delete))
+    })
+    .is_err());
+    assert!(std::panic::catch_unwind(|| {
+        Statement::for_any()
+            .denying_all()
+            .allowing(&TestActions::Post(PostActions::Read))
+    })
+    .is_err());
+
+    let statement = Statement::for_any()
+        .denying(&TestActions::Post(PostActions::Delete))
+        .denying(&TestActions::Post(PostActions::Update));
+    assert_eq!(statement.effect, Effect::Deny);
+}
+
+#[test]
+fn explain_tests() {
+    let permissions = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("posts").and(Identifier::Any))
+            .allowing(&ActionName(vec![Cow::Borrowed("Post")])),
+        Statement::for_resource(ResourceName::named("posts").and(42)).allowing_all(),
+        Statement::for_resource(ResourceName::named("posts").and(7))
+            .denying(&TestActions::Post(PostActions::Delete)),
+    ]);
+
+    match permissions.explain(
+        ResourceName::named("posts").and(1),
+        &TestActions::Post(PostActions::Read),
+    ) {
+        Decision::Allowed(matched) => {
+            assert_eq!(matched.resource.to_string(), "posts.*");
+            assert_eq!(matched.action.to_string(), "Post");
+            assert_eq!(matched.statement.resources[0].to_string(), "posts.*");
+        }
+        other => unreachable!("unexpected decision: {:?}", other),
+    }
+
+    match permissions.explain(
+        ResourceName::named("posts").and(42),
+        &TestActions::DoSomething,
+    ) {
+        Decision::Allowed(matched) => {
+            assert_eq!(matched.resource.to_string(), "posts.42");
+            assert!(matched.action.0.is_empty());
+        }
+        other => unreachable!("unexpected decision: {:?}", other),
+    }
+
+    match permissions.explain(
+        ResourceName::named("posts").and(7),
+        &TestActions::Post(PostActions::Delete),
+    ) {
+        Decision::Denied(matched) => {
+            assert_eq!(matched.resource.to_string(), "posts.7");
+            assert_eq!(matched.action.to_string(), "Post.Delete");
+        }
+        other => unreachable!("unexpected decision: {:?}", other),
+    }
+
+    let decision = permissions.explain(
+        ResourceName::named("posts").and(1),
+        &TestActions::DoSomething,
+    );
+    assert!(matches!(decision, Decision::NotAllowed));
+    assert!(!decision.is_allowed());
+
+    // Provenance survives merging.
+    let merged = Permissions::merged([&Permissions::default(), &permissions]);
+    assert!(merged
+        .explain(
+            ResourceName::named("posts").and(42),
+            &TestActions::DoSomething
+        )
+        .is_allowed());
+}
+
+#[test]
+fn allowed_actions_tests() {
+    fn names(list: ActionNameList) -> Vec<String> {
+        match list {
+            ActionNameList::List(names) => names.iter().map(ToString::to_string).collect(),
+            ActionNameList::All => unreachable!("expected a list"),
+        }
+    }
+
+    let permissions = Permissions::from(vec![
+        Statement::for_any().allowing(&TestActions::Post(PostActions::Read)),
+        Statement::for_resource(ResourceName::named("posts").and(Identifier::Any))
+            .allowing(&TestActions::Post(PostActions::Update))
+            .allowing(&TestActions::Post(PostActions::Delete)),
+        Statement::for_resource(ResourceName::named("posts").and(7))
+            .denying(&TestActions::Post(PostActions::Delete)),
+        Statement::for_resource(ResourceName::named("admin")).allowing_all(),
+    ]);
+
+    assert_eq!(
+        names(permissions.allowed_actions(ResourceName::named("other"))),
+        ["Post.Read"]
+    );
+    assert_eq!(
+        names(permissions.allowed_actions(ResourceName::named("posts").and(1))),
+        ["Post.Delete", "Post.Read", "Post.Update"]
+    );
+    assert_eq!(
+        names(permissions.allowed_actions(ResourceName::named("posts").and(7))),
+        ["Post.Read", "Post.Update"]
+    );
+    assert!(matches!(
+        permissions.allowed_actions(ResourceName::named("admin").and(1)),
+        ActionNameList::All
+    ));
+    assert!(names(Permissions::default().allowed_actions(ResourceName::named("other"))).is_empty());
+}
+
+#[test]
+fn resources_allowing_tests() {
+    let permissions = Permissions::from(vec![
+        Statement::for_resources(vec![
+            ResourceName::named("posts").and(4),
+            ResourceName::named("posts").and(7),
+            ResourceName::named("projects")
+                .and(9)
+                .and("posts")
+                .and(Identifier::Any),
+        ])
+        .allowing(&TestActions::Post(PostActions::Update)),
+        Statement::for_resource(ResourceName::named("posts").and(7).and("comments")).allowing_all(),
+        Statement::for_resource(ResourceName::named("archive")).allowing_all(),
+        Statement::for_resource(ResourceName::named("archive"))
+            .denying(&TestActions::Post(PostActions::Update)),
+    ]);
+
+    let resources = permissions
+        .resources_allowing(&TestActions::Post(PostActions::Update))
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(resources, ["posts.4", "posts.7", "projects.9.posts.*"]);
+
+    let resources = permissions
+        .resources_allowing(&TestActions::DoSomething)
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(resources, ["archive", "posts.7.comments"]);
+
+    assert_eq!(
+        Permissions::allow_all()
+            .resources_allowing(&TestActions::DoSomething)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["*"]
+    );
+}
+
+#[test]
+fn pattern_tests() {
+    let permissions = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named(Identifier::pattern("tenant-*")))
+            .allowing(&TestActions::DoSomething)
+            .with("tier", "shared"),
+        Statement::for_resource(
+            ResourceName::named(Identifier::pattern("tenant-*")).and("billing"),
+        )
+        .denying(&TestActions::DoSomething),
+        Statement::for_resource(ResourceName::named("tenant-vip")).with("tier", "dedicated"),
+    ]);
+
+    assert!(permissions.allowed_to(ResourceName::named("tenant-a"), &TestActions::DoSomething));
+    assert!(permissions.allowed_to(
+        ResourceName::named("tenant-b").and(42),
+        &TestActions::DoSomething
+    ));
+    assert!(!permissions.allowed_to(ResourceName::named("tenants"), &TestActions::DoSomething));
+    assert!(!permissions.allowed_to(
+        ResourceName::named("tenant-a").and("billing"),
+        &TestActions::DoSomething
+    ));
+
+    // Exact matches are checked before patterns.
+    assert_eq!(
+        permissions
+            .get(ResourceName::named("tenant-vip"), "tier")
+            .unwrap()
+            .to_string(),
+        "dedicated"
+    );
+    assert_eq!(
+        permissions
+            .get(ResourceName::named("tenant-a"), "tier")
+            .unwrap()
+            .to_string(),
+        "shared"
+    );
+
+    // The pattern index survives merging.
+    let merged = Permissions::merged([&Permissions::default(), &permissions]);
+    assert!(merged.allowed_to(ResourceName::named("tenant-a"), &TestActions::DoSomething));
+}
+
+#[test]
+fn recursive_tests() {
+    let permissions = Permissions::from(vec![
+        Statement::for_resource(
+            ResourceName::named("projects")
+                .and(Identifier::Recursive)
+                .and("files"),
+        )
+        .allowing(&TestActions::Post(PostActions::Read))
+        .with("quota", 10_u64),
+        Statement::for_resource(ResourceName::named("archive").and(Identifier::Recursive))
+            .allowing(&TestActions::DoSomething),
+        Statement::for_resource(ResourceName::named("archive").and(Identifier::Any))
+            .allowing(&TestActions::Post(PostActions::Read)),
+    ]);
+
+    for resource in [
+        ResourceName::named("projects").and("files"),
+        ResourceName::named("projects").and(5).and("files"),
+        ResourceName::named("projects")
+            .and(5)
+            .and("archive")
+            .and("files"),
+        ResourceName::named("projects").and(5).and("files").and(10),
+    ] {
+        assert!(permissions.allowed_to(&resource, &TestActions::Post(PostActions::Read)));
+        assert_eq!(
+            permissions.get(&resource, "quota").unwrap().to_unsigned(),
+            Some(10)
+        );
+    }
+    assert!(!permissions.allowed_to(
+        ResourceName::named("projects").and(5),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(permissions
+        .get(ResourceName::named("projects").and(5), "quota")
+        .is_none());
+
+    // A trailing `Recursive` matches its parent, unlike `Any`.
+    assert!(permissions.allowed_to(ResourceName::named("archive"), &TestActions::DoSomething));
+    assert!(!permissions.allowed_to(
+        ResourceName::named("archive"),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(permissions.allowed_to(
+        ResourceName::named("archive").and(1).and(2),
+        &TestActions::DoSomething
+    ));
+}
+
 #[derive(Actionable, Debug)]
 #[actionable(actionable = crate, async)]
 enum Request {
@@ -453,127 +762,1304 @@ async fn example() {
 }
 
 #[test]
-fn allowed_actions_merging_tests() {
-    let permissions_a = Permissions::from(vec![
-        Statement::for_resource(ResourceName::named("started_with_all")).allowing_all(),
-        Statement::for_resource(ResourceName::named("started_with_some"))
-            .allowing(&TestActions::DoSomething),
-        Statement::for_resource(ResourceName::named("nested").and("started_with_all"))
-            .allowing_all(),
-        Statement::for_resource(ResourceName::named("nested").and("started_with_some"))
-            .allowing(&TestActions::DoSomething),
+fn wildcard_action_tests() {
+    let any_read = ActionName(vec![
+        Cow::Borrowed(ActionName::WILDCARD),
+        Cow::Borrowed("Read"),
     ]);
-    let permissions_b = Permissions::from(vec![
-        Statement::for_resource(ResourceName::named("started_with_none")).allowing_all(),
-        Statement::for_resource(ResourceName::named("started_with_some")).allowing_all(),
-        Statement::for_resource(ResourceName::named("nested").and("started_with_none"))
-            .allowing(&TestActions::Post(PostActions::Read)),
-        Statement::for_resource(ResourceName::named("nested").and("started_with_some"))
-            .allowing(&TestActions::Post(PostActions::Read)),
+    let permissions = Permissions::from(vec![
+        Statement::for_any().allowing(&any_read),
+        Statement::for_resource(ResourceName::named("secret"))
+            .denying(&ActionName(vec![Cow::Borrowed(ActionName::WILDCARD)])),
     ]);
 
-    let merged = Permissions::merged([permissions_a, permissions_b].iter());
-    // For the top level, on Actions we're only testing transitioning form either
-    // None/Some to All
-    assert!(merged.allowed_to(
-        &ResourceName::named("started_with_all"),
-        &TestActions::DoSomething
+    assert!(permissions.allowed_to(
+        ResourceName::named("a"),
+        &TestActions::Post(PostActions::Read)
     ));
-    assert!(merged.allowed_to(
-        &ResourceName::named("started_with_none"),
-        &TestActions::DoSomething
+    assert!(permissions.allowed_to(
+        ResourceName::named("a"),
+        &ActionName(vec![Cow::Borrowed("User"), Cow::Borrowed("Read")])
     ));
-    assert!(merged.allowed_to(
-        &ResourceName::named("started_with_some"),
-        &TestActions::DoSomething
+    assert!(!permissions.allowed_to(
+        ResourceName::named("a"),
+        &TestActions::Post(PostActions::Update)
     ));
-    assert!(merged.allowed_to(
-        &ResourceName::named("started_with_some"),
-        &TestActions::Post(PostActions::Delete)
+    assert!(!permissions.allowed_to(ResourceName::named("a"), &TestActions::DoSomething));
+    assert!(!permissions.allowed_to(
+        ResourceName::named("a"),
+        &ActionName(vec![Cow::Borrowed("Read")])
     ));
-    // For the nested level, the transitions will only take permissions to a Some()
-    // instead of All.
-    assert!(merged.allowed_to(
-        &ResourceName::named("nested").and("started_with_none"),
+    // `*` matches exactly one segment, and its children are allowed.
+    assert!(permissions.allowed_to(
+        ResourceName::named("a"),
+        &ActionName(vec![
+            Cow::Borrowed("Post"),
+            Cow::Borrowed("Read"),
+            Cow::Borrowed("Draft")
+        ])
+    ));
+    assert!(!permissions.allowed_to(
+        ResourceName::named("secret"),
         &TestActions::Post(PostActions::Read)
     ));
-    assert!(!merged.allowed_to(
-        &ResourceName::named("nested").and("started_with_none"),
-        &TestActions::DoSomething
+    assert!(matches!(
+        permissions.explain(ResourceName::named("a"), &TestActions::Post(PostActions::Read)),
+        Decision::Allowed(matched) if matched.action.0 == any_read.0
     ));
-
-    assert!(merged.allowed_to(
-        &ResourceName::named("nested").and("started_with_some"),
-        &TestActions::Post(PostActions::Read)
+    assert!(matches!(
+        permissions.explain(
+            ResourceName::named("secret"),
+            &TestActions::Post(PostActions::Read)
+        ),
+        Decision::Denied(_)
     ));
-    assert!(merged.allowed_to(
-        &ResourceName::named("nested").and("started_with_some"),
-        &TestActions::DoSomething
+
+    // Wildcards are respected when comparing permissions.
+    let post_reader =
+        Permissions::from(Statement::for_any().allowing(&TestActions::Post(PostActions::Read)));
+    let any_reader = Permissions::from(Statement::for_any().allowing(&any_read));
+    assert!(post_reader.is_subset_of(&any_reader));
+    assert!(!any_reader.is_subset_of(&post_reader));
+    // Denying every action on `secret` also denies `Post.Read`.
+    assert!(!post_reader.is_subset_of(&permissions));
+    let post_writer =
+        Permissions::from(Statement::for_any().allowing(&ActionName(vec![Cow::Borrowed("Post")])));
+    assert!(matches!(
+        Permissions::intersection(&post_writer, &permissions)
+            .allowed_actions(ResourceName::named("a")),
+        ActionNameList::List(names) if names.len() == 1 && names[0].to_string() == "Post.Read"
     ));
 }
 
+#[derive(Debug, Action)]
+#[action(actionable = crate, parse)]
+enum DocumentActions {
+    /// Read a document.
+    Read,
+    /// Change the contents
+    /// of a document.
+    #[action(implies = "Read")]
+    Update,
+    #[action(implies = "Update")]
+    Publish,
+    #[action(implies = "*")]
+    Admin,
+}
+
+#[derive(Debug, Action)]
+#[action(actionable = crate, parse)]
+enum AppActions {
+    /// Manage documents.
+    Document(DocumentActions),
+    #[action(implies = "Document")]
+    Owner,
+    Other,
+}
+
 #[test]
-fn configuration_tests() {
-    let permissions_a = Permissions::from(vec![
-        Statement::for_resource(ResourceName::named("a")).with("u64", 0_u64),
-        Statement::for_resource(ResourceName::named("a")).with("i64", i64::MIN),
-        Statement::for_resource(ResourceName::named("a").and("b")).with("i64", 2_i64),
-        Statement::for_any().with("global", "value"),
+fn implication_tests() {
+    fn names(action: &impl Action) -> Vec<String> {
+        let mut names = action
+            .implied_by()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    assert_eq!(names(&DocumentActions::Read), [
+        "Admin", "Publish", "Update"
+    ]);
+    assert_eq!(names(&DocumentActions::Publish), ["Admin"]);
+    assert!(names(&DocumentActions::Admin).is_empty());
+    assert_eq!(names(&AppActions::Document(DocumentActions::Update)), [
+        "Document.Admin",
+        "Document.Publish",
+        "Owner"
     ]);
+    assert!(names(&AppActions::Other).is_empty());
 
+    let read = AppActions::Document(DocumentActions::Read);
+    let update = AppActions::Document(DocumentActions::Update);
+    let editor = Permissions::from(vec![
+        Statement::for_any().allowing(&update),
+        Statement::for_resource(ResourceName::named("locked")).denying(&read),
+        Statement::for_resource(ResourceName::named("frozen")).denying(&update),
+    ]);
+    assert!(editor.allowed_to(ResourceName::named("a"), &read));
+    assert!(editor.allowed_to(ResourceName::named("a"), &update));
+    assert!(!editor.allowed_to(
+        ResourceName::named("a"),
+        &AppActions::Document(DocumentActions::Publish)
+    ));
+    // Explicitly denying an action overrides its implication.
+    assert!(!editor.allowed_to(ResourceName::named("locked"), &read));
+    // Denying the implying action also prevents it from implying others.
+    assert!(!editor.allowed_to(ResourceName::named("frozen"), &read));
+    assert!(matches!(
+        editor.explain(ResourceName::named("a"), &read),
+        Decision::Allowed(matched) if matched.action.to_string() == "Document.Update"
+    ));
     assert_eq!(
-        permissions_a
-            .get(&ResourceName::named("a"), "u64")
-            .unwrap()
-            .to_unsigned(),
-        Some(0)
+        editor
+            .resources_allowing(&read)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["*"]
     );
 
-    assert_eq!(
-        permissions_a
-            .get(&ResourceName::named("a"), "u64")
-            .unwrap()
-            .to_signed(),
-        Some(0)
+    let owner = Permissions::from(
+        Statement::for_resource(ResourceName::named("mine")).allowing(&AppActions::Owner),
     );
+    assert!(owner.allowed_to(
+        ResourceName::named("mine"),
+        &AppActions::Document(DocumentActions::Admin)
+    ));
+    assert!(!owner.allowed_to(ResourceName::named("mine"), &AppActions::Other));
+}
+
+#[test]
+fn action_registry_tests() {
+    fn strings(names: &[ActionName]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    assert_eq!(strings(&TestActions::all_action_names()), [
+        "DoSomething",
+        "Post.Read",
+        "Post.Update",
+        "Post.Delete"
+    ]);
+    assert_eq!(strings(&AppActions::all_action_names()), [
+        "Document.Read",
+        "Document.Update",
+        "Document.Publish",
+        "Document.Admin",
+        "Owner",
+        "Other"
+    ]);
+
+    let registry = ActionRegistry::default()
+        .with::<TestActions>()
+        .with::<PostActions>();
+    assert_eq!(strings(registry.names()), [
+        "Delete",
+        "DoSomething",
+        "Post.Delete",
+        "Post.Read",
+        "Post.Update",
+        "Read",
+        "Update"
+    ]);
+    assert!(registry.matches(&TestActions::Post(PostActions::Read).name()));
+    assert!(registry.matches(&ActionName(vec![Cow::Borrowed("Post")])));
+    assert!(registry.matches(&ActionName(vec![
+        Cow::Borrowed(ActionName::WILDCARD),
+        Cow::Borrowed("Read")
+    ])));
+    assert!(!registry.matches(&ActionName(vec![
+        Cow::Borrowed("Post"),
+        Cow::Borrowed("Publish")
+    ])));
+
+    let statements = vec![
+        Statement::for_any()
+            .allowing(&TestActions::DoSomething)
+            .allowing(&ActionName(vec![
+                Cow::Borrowed("Post"),
+                Cow::Borrowed("Publish"),
+            ])),
+        Statement::for_any().allowing_all(),
+    ];
+    assert_eq!(strings(&registry.unknown_actions(&statements)), [
+        "Post.Publish"
+    ]);
+}
 
+#[test]
+fn action_description_tests() {
     assert_eq!(
-        permissions_a
-            .get(&ResourceName::named("a"), "u64")
-            .unwrap()
-            .to_string(),
-        "0"
+        DocumentActions::Read.description(),
+        Some("Read a document.")
     );
-
     assert_eq!(
-        permissions_a
-            .get(&ResourceName::named("a"), "i64")
-            .unwrap()
-            .to_unsigned(),
-        None
+        DocumentActions::Update.description(),
+        Some("Change the contents\nof a document.")
     );
-
+    assert_eq!(DocumentActions::Admin.description(), None);
     assert_eq!(
-        permissions_a
-            .get(&ResourceName::named("a"), "i64")
-            .unwrap()
-            .to_signed(),
-        Some(i64::MIN)
+        AppActions::Document(DocumentActions::Read).description(),
+        Some("Read a document.")
     );
-
+    // Subactions without a description fall back to the variant's.
     assert_eq!(
-        permissions_a
-            .get(&ResourceName::named("a").and("b"), "i64")
-            .unwrap()
-            .to_signed(),
-        Some(2)
+        AppActions::Document(DocumentActions::Publish).description(),
+        Some("Manage documents.")
     );
+    assert_eq!(AppActions::Owner.description(), None);
 
+    let registry = ActionRegistry::default().with::<AppActions>();
+    for name in AppActions::all_action_names() {
+        let action = AppActions::try_from(&name).unwrap();
+        assert_eq!(registry.description(&name), action.description());
+    }
+    assert_eq!(registry.description(&"Document".parse().unwrap()), None);
     assert_eq!(
-        permissions_a
-            .get(&ResourceName::named("a").and("b"), "global")
-            .unwrap()
-            .to_string(),
+        AppActions::description_of(&"Document".parse().unwrap()),
+        Some("Manage documents.")
+    );
+    assert_eq!(
+        AppActions::description_of(&"Other.Read".parse().unwrap()),
+        None
+    );
+}
+
+#[test]
+fn action_parsing_tests() {
+    assert!(matches!(
+        "DoSomething".parse::<TestActions>(),
+        Ok(TestActions::DoSomething)
+    ));
+    assert!(matches!(
+        "Post.Read".parse::<TestActions>(),
+        Ok(TestActions::Post(PostActions::Read))
+    ));
+    assert!(matches!(
+        TestActions::try_from(&TestActions::Post(PostActions::Delete).name()),
+        Ok(TestActions::Post(PostActions::Delete))
+    ));
+    assert!(matches!(
+        "Document.Publish".parse::<AppActions>(),
+        Ok(AppActions::Document(DocumentActions::Publish))
+    ));
+    // Every derived name round-trips.
+    for name in TestActions::all_action_names() {
+        assert_eq!(TestActions::try_from(&name).unwrap().name(), name);
+    }
+
+    assert_eq!(
+        "Post.Publish".parse::<TestActions>().unwrap_err(),
+        ActionNameError::UnknownSegment {
+            name: String::from("Post.Publish"),
+            segment: String::from("Publish"),
+            position: 1,
+        }
+    );
+    assert_eq!(
+        "Other".parse::<TestActions>().unwrap_err(),
+        ActionNameError::UnknownSegment {
+            name: String::from("Other"),
+            segment: String::from("Other"),
+            position: 0,
+        }
+    );
+    assert_eq!(
+        "Post".parse::<TestActions>().unwrap_err(),
+        ActionNameError::Incomplete {
+            name: String::from("Post"),
+        }
+    );
+    assert_eq!(
+        "Post.Read.Draft".parse::<TestActions>().unwrap_err(),
+        ActionNameError::UnexpectedSegment {
+            name: String::from("Post.Read.Draft"),
+            segment: String::from("Draft"),
+            position: 2,
+        }
+    );
+    assert_eq!(
+        "Post..Read".parse::<TestActions>().unwrap_err(),
+        ActionNameError::EmptySegment {
+            name: String::from("Post..Read"),
+        }
+    );
+
+    assert_eq!(
+        "Post.Read".parse::<ActionName>().unwrap(),
+        TestActions::Post(PostActions::Read).name()
+    );
+    assert_eq!("".parse::<ActionName>().unwrap(), ActionName::default());
+    let mut counts = std::collections::HashMap::new();
+    *counts.entry(TestActions::DoSomething.name()).or_insert(0) += 1;
+    *counts
+        .entry("DoSomething".parse::<ActionName>().unwrap())
+        .or_insert(0) += 1;
+    assert_eq!(counts.len(), 1);
+}
+
+#[derive(Debug, Action)]
+#[action(actionable = crate, parse, rename_all = "snake_case")]
+enum StoredActions {
+    ReadAll,
+    #[action(rename = "delete")]
+    Remove,
+    #[action(implies = "ReadAll")]
+    Manage,
+    PostComment(PostActions),
+}
+
+#[test]
+fn renamed_action_tests() {
+    fn strings(names: &[ActionName]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    assert_eq!(StoredActions::ReadAll.name().to_string(), "read_all");
+    assert_eq!(StoredActions::Remove.name().to_string(), "delete");
+    assert_eq!(
+        StoredActions::PostComment(PostActions::Read)
+            .name()
+            .to_string(),
+        "post_comment.Read"
+    );
+    assert_eq!(strings(&StoredActions::all_action_names()), [
+        "read_all",
+        "delete",
+        "manage",
+        "post_comment.Read",
+        "post_comment.Update",
+        "post_comment.Delete"
+    ]);
+    assert_eq!(strings(&StoredActions::ReadAll.implied_by()), ["manage"]);
+
+    assert!(matches!(
+        "delete".parse::<StoredActions>(),
+        Ok(StoredActions::Remove)
+    ));
+    assert!(matches!(
+        "post_comment.Update".parse::<StoredActions>(),
+        Ok(StoredActions::PostComment(PostActions::Update))
+    ));
+    assert!("Remove".parse::<StoredActions>().is_err());
+
+    let permissions =
+        Permissions::from(vec![Statement::for_any().allowing(&StoredActions::Manage)]);
+    assert!(permissions.allowed_to(&ResourceName::any(), &StoredActions::ReadAll));
+    assert!(!permissions.allowed_to(&ResourceName::any(), &StoredActions::Remove));
+}
+
+#[derive(Debug, Action)]
+#[action(actionable = crate, parse)]
+enum RequestActions {
+    Delete {
+        #[action(skip)]
+        soft: bool,
+    },
+    Archive(#[action(skip)] u64),
+    Comment {
+        action: PostActions,
+        #[action(skip)]
+        id: u64,
+    },
+    Tagged(#[action(skip)] String, PostActions),
+}
+
+#[test]
+fn skipped_field_action_tests() {
+    assert_eq!(
+        RequestActions::Delete { soft: true }.name().to_string(),
+        "Delete"
+    );
+    assert_eq!(RequestActions::Archive(1).name().to_string(), "Archive");
+    assert_eq!(
+        RequestActions::Comment {
+            action: PostActions::Update,
+            id: 1,
+        }
+        .name()
+        .to_string(),
+        "Comment.Update"
+    );
+    assert_eq!(
+        RequestActions::Tagged(String::from("tag"), PostActions::Read)
+            .name()
+            .to_string(),
+        "Tagged.Read"
+    );
+    assert_eq!(RequestActions::all_action_names().len(), 8);
+
+    // Skipped fields are initialized with their default values.
+    assert!(matches!(
+        "Delete".parse::<RequestActions>(),
+        Ok(RequestActions::Delete { soft: false })
+    ));
+    assert!(matches!(
+        "Archive".parse::<RequestActions>(),
+        Ok(RequestActions::Archive(0))
+    ));
+    assert!(matches!(
+        "Comment.Delete".parse::<RequestActions>(),
+        Ok(RequestActions::Comment {
+            action: PostActions::Delete,
+            id: 0
+        })
+    ));
+    assert!(matches!(
+        "Tagged.Read".parse::<RequestActions>(),
+        Ok(RequestActions::Tagged(tag, PostActions::Read)) if tag.is_empty()
+    ));
+
+    let permissions = Permissions::from(vec![Statement::for_any()
+        .allowing(&RequestActions::Delete { soft: false })
+        .allowing(&"Comment.Read".parse::<ActionName>().unwrap())]);
+    assert!(permissions.allowed_to(&ResourceName::any(), &RequestActions::Delete { soft: true }));
+    assert!(
+        permissions.allowed_to(&ResourceName::any(), &RequestActions::Comment {
+            action: PostActions::Read,
+            id: 42,
+        })
+    );
+    assert!(
+        !permissions.allowed_to(&ResourceName::any(), &RequestActions::Comment {
+            action: PostActions::Update,
+            id: 42,
+        })
+    );
+}
+
+/// Subactions with a hand-written `Action` implementation, which can't be
+/// parsed.
+#[derive(Debug, Action)]
+#[action(actionable = crate)]
+enum ForwardedActions {
+    Named(ActionName),
+    Unit(()),
+}
+
+#[test]
+fn unparsed_subaction_tests() {
+    let named = ForwardedActions::Named("Post.Read".parse().unwrap());
+    assert_eq!(named.name().to_string(), "Named.Post.Read");
+    assert_eq!(ForwardedActions::Unit(()).name().to_string(), "Unit");
+    assert!(ForwardedActions::all_action_names().is_empty());
+
+    let permissions = Permissions::from(vec![Statement::for_any().allowing(&named)]);
+    assert!(permissions.allowed_to(ResourceName::any(), &named));
+    assert!(!permissions.allowed_to(ResourceName::any(), &ForwardedActions::Unit(())));
+}
+
+#[test]
+fn allowed_actions_merging_tests() {
+    let permissions_a = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("started_with_all")).allowing_all(),
+        Statement::for_resource(ResourceName::named("started_with_some"))
+            .allowing(&TestActions::DoSomething),
+        Statement::for_resource(ResourceName::named("nested").and("started_with_all"))
+            .allowing_all(),
+        Statement::for_resource(ResourceName::named("nested").and("started_with_some"))
+            .allowing(&TestActions::DoSomething),
+    ]);
+    let permissions_b = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("started_with_none")).allowing_all(),
+        Statement::for_resource(ResourceName::named("started_with_some")).allowing_all(),
+        Statement::for_resource(ResourceName::named("nested").and("started_with_none"))
+            .allowing(&TestActions::Post(PostActions::Read)),
+        Statement::for_resource(ResourceName::named("nested").and("started_with_some"))
+            .allowing(&TestActions::Post(PostActions::Read)),
+    ]);
+
+    let merged = Permissions::merged([permissions_a, permissions_b].iter());
+    // For the top level, on Actions we're only testing transitioning form either
+    // None/Some to All
+    assert!(merged.allowed_to(
+        &ResourceName::named("started_with_all"),
+        &TestActions::DoSomething
+    ));
+    assert!(merged.allowed_to(
+        &ResourceName::named("started_with_none"),
+        &TestActions::DoSomething
+    ));
+    assert!(merged.allowed_to(
+        &ResourceName::named("started_with_some"),
+        &TestActions::DoSomething
+    ));
+    assert!(merged.allowed_to(
+        &ResourceName::named("started_with_some"),
+        &TestActions::Post(PostActions::Delete)
+    ));
+    // For the nested level, the transitions will only take permissions to a Some()
+    // instead of All.
+    assert!(merged.allowed_to(
+        &ResourceName::named("nested").and("started_with_none"),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(!merged.allowed_to(
+        &ResourceName::named("nested").and("started_with_none"),
+        &TestActions::DoSomething
+    ));
+
+    assert!(merged.allowed_to(
+        &ResourceName::named("nested").and("started_with_some"),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(merged.allowed_to(
+        &ResourceName::named("nested").and("started_with_some"),
+        &TestActions::DoSomething
+    ));
+}
+
+#[test]
+fn intersection_tests() {
+    let user = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("posts").and(Identifier::Any)).allowing_all(),
+        Statement::for_resource(ResourceName::named("users").and(1)).allowing_all(),
+    ]);
+    let token = Permissions::from(vec![
+        Statement::for_any().allowing(&TestActions::Post(PostActions::Read)),
+        Statement::for_resource(ResourceName::named("posts").and(4))
+            .allowing(&TestActions::Post(PostActions::Update)),
+    ]);
+    let intersection = Permissions::intersection(&user, &token);
+
+    assert!(intersection.allowed_to(
+        ResourceName::named("posts").and(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(!intersection.allowed_to(
+        ResourceName::named("posts").and(1),
+        &TestActions::Post(PostActions::Update)
+    ));
+    assert!(intersection.allowed_to(
+        ResourceName::named("posts").and(4),
+        &TestActions::Post(PostActions::Update)
+    ));
+    // The token allows reading anything, but the user can't read this.
+    assert!(!intersection.allowed_to(
+        ResourceName::named("comments").and(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(matches!(
+        intersection.explain(
+            ResourceName::named("posts").and(1),
+            &TestActions::Post(PostActions::Delete)
+        ),
+        Decision::NotAllowed
+    ));
+
+    match intersection.allowed_actions(ResourceName::named("posts").and(4)) {
+        ActionNameList::List(names) =>
+            assert_eq!(names.iter().map(ToString::to_string).collect::<Vec<_>>(), [
+                "Post.Read",
+                "Post.Update"
+            ]),
+        ActionNameList::All => unreachable!("expected a list"),
+    }
+
+    assert_eq!(
+        intersection
+            .resources_allowing(&TestActions::Post(PostActions::Update))
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["posts.4"]
+    );
+}
+
+#[test]
+fn merged_intersection_tests() {
+    let intersection = Permissions::intersection(
+        &Permissions::from(
+            Statement::for_resource(ResourceName::named("posts").and(Identifier::Any))
+                .allowing_all(),
+        ),
+        &Permissions::from(Statement::for_any().allowing(&TestActions::Post(PostActions::Read))),
+    );
+
+    // Merging an intersection doesn't limit the other merged permissions.
+    let merged = Permissions::merged([&intersection, &Permissions::allow_all()]);
+    assert!(merged.allowed_to(
+        ResourceName::named("comments").and(1),
+        &TestActions::Post(PostActions::Delete)
+    ));
+
+    // Each merged intersection stays limited by its own permissions.
+    let commenter = Permissions::intersection(
+        &Permissions::from(
+            Statement::for_resource(ResourceName::named("comments").and(Identifier::Any))
+                .allowing_all(),
+        ),
+        &Permissions::from(Statement::for_any().allowing(&TestActions::Post(PostActions::Delete))),
+    );
+    let merged = Permissions::merged([&intersection, &commenter]);
+    assert!(merged.allowed_to(
+        ResourceName::named("comments").and(1),
+        &TestActions::Post(PostActions::Delete)
+    ));
+    assert!(!merged.allowed_to(
+        ResourceName::named("comments").and(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(merged.allowed_to(
+        ResourceName::named("posts").and(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(!merged.allowed_to(
+        ResourceName::named("posts").and(1),
+        &TestActions::Post(PostActions::Delete)
+    ));
+    assert!(merged
+        .explain(
+            ResourceName::named("comments").and(1),
+            &TestActions::Post(PostActions::Delete)
+        )
+        .is_allowed());
+
+    // Denials still apply to the entire merged result.
+    let merged = Permissions::merged([
+        &commenter,
+        &Permissions::from(
+            Statement::for_resource(ResourceName::named("comments").and(1))
+                .denying(&TestActions::Post(PostActions::Delete)),
+        ),
+    ]);
+    assert!(!merged.allowed_to(
+        ResourceName::named("comments").and(1),
+        &TestActions::Post(PostActions::Delete)
+    ));
+    assert!(matches!(
+        merged.explain(
+            ResourceName::named("comments").and(1),
+            &TestActions::Post(PostActions::Delete)
+        ),
+        Decision::Denied(_)
+    ));
+    assert!(merged.allowed_to(
+        ResourceName::named("comments").and(2),
+        &TestActions::Post(PostActions::Delete)
+    ));
+}
+
+#[test]
+fn subset_tests() {
+    let admin = Permissions::from(vec![
+        Statement::allow_all_for_any_resource(),
+        Statement::for_resource("billing").denying_all(),
+    ]);
+    let editor = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("posts").and(Identifier::Any))
+            .allowing(&ActionName(vec![Cow::Borrowed("Post")])),
+        Statement::for_resource(ResourceName::named("posts").and(7))
+            .denying(&TestActions::Post(PostActions::Delete)),
+    ]);
+    let reader = Permissions::from(vec![Statement::for_resource(
+        ResourceName::named("posts").and(Identifier::Any),
+    )
+    .allowing(&TestActions::Post(PostActions::Read))]);
+    let single_post = Permissions::from(vec![Statement::for_resource(
+        ResourceName::named("posts").and(4),
+    )
+    .allowing_all()]);
+    let billing =
+        Permissions::from(Statement::for_resource("billing").allowing(&TestActions::DoSomething));
+
+    assert!(reader.is_subset_of(&editor));
+    assert!(editor.is_subset_of(&admin));
+    assert!(reader.is_subset_of(&admin));
+    assert!(!admin.is_subset_of(&editor));
+    assert!(!billing.is_subset_of(&admin));
+    // A single resource doesn't cover a wildcard.
+    assert!(!reader.is_subset_of(&single_post));
+    assert!(!single_post.is_subset_of(&reader));
+    assert!(Permissions::default().is_subset_of(&Permissions::default()));
+
+    let difference = editor.difference(&reader);
+    assert_eq!(difference.len(), 1);
+    assert_eq!(difference[0].resource.to_string(), "posts.*");
+    assert_eq!(difference[0].action.to_string(), "Post");
+
+    // Editors can delete most posts, which is more than someone who can't
+    // delete post 7.
+    let restricted = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("posts").and(Identifier::Any)).allowing_all(),
+        Statement::for_resource(ResourceName::named("posts").and(4))
+            .denying(&TestActions::Post(PostActions::Delete)),
+    ]);
+    let difference = editor.difference(&restricted);
+    assert_eq!(difference.len(), 1);
+    assert_eq!(difference[0].resource.to_string(), "posts.4");
+    assert_eq!(difference[0].action.to_string(), "Post.Delete");
+    assert!(!editor.is_subset_of(&restricted));
+
+    // A recursive grant isn't covered by a single-segment wildcard.
+    let recursive = Permissions::from(
+        Statement::for_resource(ResourceName::named("posts").and(Identifier::Recursive))
+            .allowing(&TestActions::Post(PostActions::Read)),
+    );
+    assert!(!recursive.is_subset_of(&reader));
+    assert!(reader.is_subset_of(&recursive));
+}
+
+#[tokio::test]
+async fn dispatch_with_all_tests() {
+    let user = Permissions::from(Statement::for_resource(42).allowing_all());
+    let token = Permissions::from(Statement::for_any().allowing(&TestActions::DoSomething));
+    let restricted = Permissions::from(Statement::for_resource(1).allowing_all());
+
+    assert_eq!(
+        TestDispatcher
+            .dispatch_with_all(&[&user, &token], Request::SimplyProtectedEnumParameter(42))
+            .await
+            .unwrap(),
+        Some(42)
+    );
+    assert!(matches!(
+        TestDispatcher
+            .dispatch_with_all(
+                &[&user, &restricted],
+                Request::SimplyProtectedEnumParameter(42)
+            )
+            .await,
+        Err(TestError::PermissionDenied(_))
+    ));
+    assert!(matches!(
+        TestDispatcher
+            .dispatch_with_all(&[], Request::SimplyProtectedEnumParameter(42))
+            .await,
+        Err(TestError::PermissionDenied(_))
+    ));
+}
+
+#[test]
+fn condition_tests() {
+    let business_hours =
+        Condition::greater_than_or_equal("hour", 9_u64).and(Condition::less_than("hour", 17_u64));
+    let permissions = Permissions::from(vec![
+        Statement::for_any()
+            .allowing(&TestActions::Post(PostActions::Read))
+            .when(business_hours.clone()),
+        Statement::for_any()
+            .allowing(&TestActions::Post(PostActions::Update))
+            .when(Condition::starts_with("source", "10.")),
+        Statement::for_resource(ResourceName::named("archive"))
+            .denying(&TestActions::Post(PostActions::Read))
+            .when(Condition::equals("hour", 12_u64)),
+    ]);
+    let read = TestActions::Post(PostActions::Read);
+    let update = TestActions::Post(PostActions::Update);
+    let morning = Context::default().with("hour", 10_u64);
+    let noon = Context::default().with("hour", 12_u64);
+    let night = Context::default().with("hour", 22_u64);
+    let internal = Context::default().with("source", "10.1.2.3");
+
+    // Without a context, conditions aren't met.
+    assert!(!permissions.allowed_to(ResourceName::named("a"), &read));
+    assert!(permissions.allowed_to_with(ResourceName::named("a"), &read, &morning));
+    assert!(permissions.allowed_to_with(ResourceName::named("a"), &read, &noon));
+    assert!(!permissions.allowed_to_with(ResourceName::named("a"), &read, &night));
+    assert!(!permissions.allowed_to_with(ResourceName::named("a"), &update, &morning));
+    assert!(permissions.allowed_to_with(ResourceName::named("a"), &update, &internal));
+    assert!(permissions
+        .check_with(ResourceName::named("a"), &update, &internal)
+        .is_ok());
+
+    // Conditional denials only apply when their condition is met.
+    assert!(permissions.allowed_to_with(ResourceName::named("archive"), &read, &morning));
+    assert!(!permissions.allowed_to_with(ResourceName::named("archive"), &read, &noon));
+    assert!(matches!(
+        permissions.explain_with(ResourceName::named("archive"), &read, &noon),
+        Decision::Denied(_)
+    ));
+    assert!(matches!(
+        permissions.explain_with(ResourceName::named("archive"), &read, &morning),
+        Decision::Allowed(_)
+    ));
+    assert!(matches!(
+        permissions.explain(ResourceName::named("archive"), &read),
+        Decision::NotAllowed
+    ));
+
+    // Conditional grants are still grants when comparing permissions.
+    let reader = Permissions::from(Statement::for_any().allowing(&read));
+    assert!(!permissions.is_subset_of(&reader));
+    assert_eq!(permissions.difference(&reader).len(), 1);
+
+    // Conditional grants only cover grants with the same condition, even if
+    // an empty context would meet the condition.
+    let business_reader =
+        Permissions::from(Statement::for_any().allowing(&read).when(business_hours));
+    let external_reader = Permissions::from(
+        Statement::for_any()
+            .allowing(&read)
+            .when(Condition::Not(Box::new(Condition::exists("source")))),
+    );
+    assert!(business_reader.is_subset_of(&reader));
+    assert!(!reader.is_subset_of(&business_reader));
+    assert!(!reader.is_subset_of(&external_reader));
+    assert!(business_reader.is_subset_of(&permissions));
+
+    // Conditional denials always limit what `other` allows.
+    let archive_denied = Permissions::from(vec![
+        Statement::for_any().allowing(&read),
+        Statement::for_resource(ResourceName::named("archive"))
+            .denying(&read)
+            .when(Condition::equals("hour", 12_u64)),
+    ]);
+    assert!(!reader.is_subset_of(&archive_denied));
+}
+
+#[test]
+fn validity_tests() {
+    let now = SystemTime::now();
+    let step = Duration::from_secs(1_000);
+    let read = TestActions::Post(PostActions::Read);
+    let update = TestActions::Post(PostActions::Update);
+    let permissions = Permissions::from(vec![
+        Statement::for_any().allowing(&read).expiring_at(now + step),
+        Statement::for_any()
+            .allowing(&update)
+            .not_before(now + step * 2)
+            .expiring_at(now + step * 3),
+        Statement::for_resource(ResourceName::named("archive"))
+            .denying(&read)
+            .not_before(now - step)
+            .expiring_at(now + step * 4),
+    ]);
+
+    let in_a_bit = Context::default().at(now + step / 2);
+    let later = Context::default().at(now + step * 2);
+    let much_later = Context::default().at(now + step * 5);
+
+    // Without a time, the current time is used.
+    assert!(permissions.allowed_to(ResourceName::named("a"), &read));
+    assert!(!permissions.allowed_to(ResourceName::named("a"), &update));
+    assert!(!permissions.allowed_to(ResourceName::named("archive"), &read));
+
+    assert!(permissions.allowed_to_with(ResourceName::named("a"), &read, &in_a_bit));
+    assert!(!permissions.allowed_to_with(ResourceName::named("a"), &update, &in_a_bit));
+    assert!(!permissions.allowed_to_with(ResourceName::named("a"), &read, &later));
+    assert!(permissions.allowed_to_with(ResourceName::named("a"), &update, &later));
+    assert!(!permissions.allowed_to_with(ResourceName::named("a"), &update, &much_later));
+    assert!(permissions.allowed_to_with(ResourceName::named("archive"), &update, &later));
+    assert!(!permissions.allowed_to_with(ResourceName::named("archive"), &read, &in_a_bit));
+
+    assert_eq!(permissions.next_expiry(), Some(now + step));
+    let restricted = Permissions::intersection(
+        &permissions,
+        &Permissions::from(
+            Statement::for_any()
+                .allowing_all()
+                .expiring_at(now + step / 4),
+        ),
+    );
+    assert_eq!(restricted.next_expiry(), Some(now + step / 4));
+    assert_eq!(
+        permissions.next_expiry_after(now + step),
+        Some(now + step * 2)
+    );
+    assert_eq!(permissions.next_expiry_after(now + step * 4), None);
+    assert_eq!(Permissions::allow_all().next_expiry(), None);
+
+    // Grants with a validity period only cover grants with the same period.
+    let reader = Permissions::from(Statement::for_any().allowing(&read));
+    let expiring_reader =
+        Permissions::from(Statement::for_any().allowing(&read).expiring_at(now + step));
+    assert!(expiring_reader.is_subset_of(&reader));
+    assert!(!reader.is_subset_of(&expiring_reader));
+    assert!(expiring_reader.is_subset_of(&permissions));
+    assert!(!expiring_reader.is_subset_of(&restricted));
+}
+
+#[test]
+fn variable_tests() {
+    let permissions = Permissions::from(vec![
+        Statement::for_resource(
+            ResourceName::named("users").and(Identifier::variable("principal.id")),
+        )
+        .allowing_all()
+        .with("quota", 10_u64),
+        Statement::for_resource(
+            ResourceName::named("teams")
+                .and(Identifier::variable("principal.team"))
+                .and(Identifier::Any),
+        )
+        .allowing(&TestActions::Post(PostActions::Read)),
+    ]);
+    let alice = Context::default()
+        .with("principal.id", 42_u64)
+        .with("principal.team", "blue");
+    let bob = Context::default().with("principal.id", 7_u64);
+
+    assert!(permissions.allowed_to_with(
+        ResourceName::named("users").and(42),
+        &TestActions::DoSomething,
+        &alice
+    ));
+    assert!(!permissions.allowed_to_with(
+        ResourceName::named("users").and(7),
+        &TestActions::DoSomething,
+        &alice
+    ));
+    assert!(permissions.allowed_to_with(
+        ResourceName::named("users").and(7),
+        &TestActions::DoSomething,
+        &bob
+    ));
+    assert!(!permissions.allowed_to(
+        ResourceName::named("users").and(42),
+        &TestActions::DoSomething
+    ));
+    assert!(permissions.allowed_to_with(
+        ResourceName::named("teams").and("blue").and(1),
+        &TestActions::Post(PostActions::Read),
+        &alice
+    ));
+    assert!(!permissions.allowed_to_with(
+        ResourceName::named("teams").and("red").and(1),
+        &TestActions::Post(PostActions::Read),
+        &alice
+    ));
+    // Without the variable in the context, nothing matches.
+    assert!(!permissions.allowed_to_with(
+        ResourceName::named("teams").and("blue").and(1),
+        &TestActions::Post(PostActions::Read),
+        &bob
+    ));
+
+    assert_eq!(
+        permissions
+            .get_with(ResourceName::named("users").and(42), "quota", &alice)
+            .and_then(Configuration::to_unsigned),
+        Some(10)
+    );
+    assert!(permissions
+        .get(ResourceName::named("users").and(42), "quota")
+        .is_none());
+}
+
+#[test]
+fn requested_variable_tests() {
+    let permissions = Permissions::from(
+        Statement::for_resource(
+            ResourceName::named("users").and(Identifier::variable("principal.id")),
+        )
+        .allowing_all(),
+    );
+    let alice = Context::default().with("principal.id", 42_u64);
+
+    // A requested variable doesn't name a resource, so it never matches a
+    // variable in a statement, even one with the same key.
+    let requested = "users.${principal.id}".parse::<ResourceName<'_>>().unwrap();
+    assert!(!permissions.allowed_to(&requested, &TestActions::DoSomething));
+    assert!(!permissions.allowed_to_with(&requested, &TestActions::DoSomething, &alice));
+    assert!(matches!(
+        permissions.explain(&requested, &TestActions::DoSomething),
+        Decision::NotAllowed
+    ));
+
+    // Comparing permissions matches variables as they are written.
+    assert!(permissions.is_subset_of(&permissions));
+    let resources = permissions.resources_allowing(&TestActions::DoSomething);
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0].to_string(), requested.to_string());
+    let own_user_denied = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("users").and(Identifier::Any)).allowing_all(),
+        Statement::for_resource(
+            ResourceName::named("users").and(Identifier::variable("principal.id")),
+        )
+        .denying_all(),
+    ]);
+    assert!(!permissions.is_subset_of(&own_user_denied));
+}
+
+#[derive(Actionable, Debug)]
+#[actionable(actionable = crate, async)]
+enum ContextRequest {
+    #[actionable(protection = "simple")]
+    ReadPost(u64),
+}
+
+#[derive(AsyncDispatcher, Debug)]
+#[dispatcher(input = ContextRequest, actionable = crate)]
+struct ContextDispatcher {
+    hour: u64,
+}
+
+#[async_trait::async_trait]
+impl ContextRequestDispatcher for ContextDispatcher {
+    type Error = TestError;
+    type Output = u64;
+}
+
+#[async_trait::async_trait]
+impl ReadPostHandler for ContextDispatcher {
+    type Action = TestActions;
+
+    async fn resource_name<'a>(&'a self, arg1: &'a u64) -> Result<ResourceName<'a>, TestError> {
+        Ok(ResourceName::named(*arg1))
+    }
+
+    fn action() -> Self::Action {
+        TestActions::Post(PostActions::Read)
+    }
+
+    fn context<'a>(&'a self, _arg1: &'a u64) -> Result<Context, TestError> {
+        Ok(Context::default().with("hour", self.hour))
+    }
+
+    async fn handle_protected(
+        &self,
+        _permissions: &Permissions,
+        arg1: u64,
+    ) -> Result<u64, TestError> {
+        Ok(arg1)
+    }
+}
+
+#[tokio::test]
+async fn dispatch_context_tests() {
+    let permissions = Permissions::from(
+        Statement::for_any()
+            .allowing(&TestActions::Post(PostActions::Read))
+            .when(Condition::less_than("hour", 17_u64)),
+    );
+    assert_eq!(
+        ContextDispatcher { hour: 10 }
+            .dispatch(&permissions, ContextRequest::ReadPost(1))
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(matches!(
+        ContextDispatcher { hour: 20 }
+            .dispatch(&permissions, ContextRequest::ReadPost(1))
+            .await,
+        Err(TestError::PermissionDenied(_))
+    ));
+}
+
+#[test]
+fn role_registry_tests() {
+    let mut registry = RoleRegistry::default();
+    registry
+        .define(
+            "reader",
+            Role::new(vec![
+                Statement::for_any().allowing(&TestActions::Post(PostActions::Read))
+            ]),
+        )
+        .unwrap();
+    registry
+        .define(
+            "editor",
+            Role::new(vec![
+                Statement::for_any().allowing(&TestActions::Post(PostActions::Update))
+            ])
+            .inheriting("reader"),
+        )
+        .unwrap();
+    registry
+        .define(
+            "operator",
+            Role::new(vec![
+                Statement::for_any().allowing(&TestActions::DoSomething)
+            ]),
+        )
+        .unwrap();
+
+    let editor = registry.permissions_for(&["editor"]).unwrap();
+    assert!(editor.allowed_to(
+        ResourceName::named(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(editor.allowed_to(
+        ResourceName::named(1),
+        &TestActions::Post(PostActions::Update)
+    ));
+    assert!(!editor.allowed_to(ResourceName::named(1), &TestActions::DoSomething));
+
+    let both = registry.permissions_for(&["operator", "editor"]).unwrap();
+    assert!(both.allowed_to(ResourceName::named(1), &TestActions::DoSomething));
+    assert!(both.allowed_to(
+        ResourceName::named(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+
+    assert_eq!(
+        registry.permissions_for(&["editor", "admin"]).unwrap_err(),
+        RoleError::UnknownRole(String::from("admin"))
+    );
+
+    // Cycles are rejected and leave the existing definition in place.
+    assert_eq!(
+        registry
+            .define("reader", Role::default().inheriting("editor"))
+            .unwrap_err(),
+        RoleError::Cycle(vec![
+            String::from("reader"),
+            String::from("editor"),
+            String::from("reader")
+        ])
+    );
+    assert!(registry
+        .define("self", Role::default().inheriting("self"))
+        .is_err());
+    assert!(registry.role("self").is_none());
+    assert!(registry.permissions_for(&["editor"]).unwrap().allowed_to(
+        ResourceName::named(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+
+    // Changing a role invalidates the permissions that inherit from it.
+    registry
+        .define(
+            "reader",
+            Role::new(vec![
+                Statement::for_any().allowing(&TestActions::Post(PostActions::Delete))
+            ]),
+        )
+        .unwrap();
+    let editor = registry.permissions_for(&["editor"]).unwrap();
+    assert!(!editor.allowed_to(
+        ResourceName::named(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(editor.allowed_to(
+        ResourceName::named(1),
+        &TestActions::Post(PostActions::Delete)
+    ));
+
+    registry.remove("reader");
+    assert_eq!(
+        registry.permissions_for(&["editor"]).unwrap_err(),
+        RoleError::UnknownRole(String::from("reader"))
+    );
+}
+
+#[tokio::test]
+async fn boundary_tests() {
+    let tenant_boundary = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("tenant-a").and(Identifier::Recursive))
+            .allowing_all(),
+        Statement::for_resource(ResourceName::named("tenant-a").and("billing")).denying_all(),
+    ]);
+    let admin = Permissions::allow_all().bounded_by(&tenant_boundary);
+
+    assert!(admin.allowed_to(
+        ResourceName::named("tenant-a").and(1),
+        &TestActions::DoSomething
+    ));
+    assert!(!admin.allowed_to(
+        ResourceName::named("tenant-b").and(1),
+        &TestActions::DoSomething
+    ));
+    assert!(!admin.allowed_to(
+        ResourceName::named("tenant-a").and("billing"),
+        &TestActions::DoSomething
+    ));
+
+    // The boundary doesn't grant anything on its own.
+    let reader =
+        Permissions::from(Statement::for_any().allowing(&TestActions::Post(PostActions::Read)))
+            .bounded_by(&tenant_boundary);
+    assert!(!reader.allowed_to(
+        ResourceName::named("tenant-a").and(1),
+        &TestActions::DoSomething
+    ));
+    assert!(reader.allowed_to(
+        ResourceName::named("tenant-a").and(1),
+        &TestActions::Post(PostActions::Read)
+    ));
+
+    // A boundary only limits the permissions it bounds, so merged roles are
+    // bounded after merging.
+    let merged = Permissions::merged(&[reader, Permissions::allow_all()]);
+    assert!(merged.allowed_to(
+        ResourceName::named("tenant-b").and(1),
+        &TestActions::DoSomething
+    ));
+    let bounded = merged.bounded_by(&tenant_boundary);
+    assert!(!bounded.allowed_to(
+        ResourceName::named("tenant-b").and(1),
+        &TestActions::DoSomething
+    ));
+    assert!(bounded.allowed_to(
+        ResourceName::named("tenant-a").and(1),
+        &TestActions::DoSomething
+    ));
+
+    // Dispatchers accept bounded permissions like any other permissions.
+    let boundary = Permissions::from(Statement::for_resource(42).allowing_all());
+    let bounded = Permissions::allow_all().bounded_by(&boundary);
+    assert_eq!(
+        TestDispatcher
+            .dispatch(&bounded, Request::SimplyProtectedEnumParameter(42))
+            .await
+            .unwrap(),
+        Some(42)
+    );
+    assert!(matches!(
+        TestDispatcher
+            .dispatch(&bounded, Request::SimplyProtectedEnumParameter(1))
+            .await,
+        Err(TestError::PermissionDenied(_))
+    ));
+}
+
+#[test]
+fn configuration_tests() {
+    let permissions_a = Permissions::from(vec![
+        Statement::for_resource(ResourceName::named("a")).with("u64", 0_u64),
+        Statement::for_resource(ResourceName::named("a")).with("i64", i64::MIN),
+        Statement::for_resource(ResourceName::named("a").and("b")).with("i64", 2_i64),
+        Statement::for_any().with("global", "value"),
+    ]);
+
+    assert_eq!(
+        permissions_a
+            .get(&ResourceName::named("a"), "u64")
+            .unwrap()
+            .to_unsigned(),
+        Some(0)
+    );
+
+    assert_eq!(
+        permissions_a
+            .get(&ResourceName::named("a"), "u64")
+            .unwrap()
+            .to_signed(),
+        Some(0)
+    );
+
+    assert_eq!(
+        permissions_a
+            .get(&ResourceName::named("a"), "u64")
+            .unwrap()
+            .to_string(),
+        "0"
+    );
+
+    assert_eq!(
+        permissions_a
+            .get(&ResourceName::named("a"), "i64")
+            .unwrap()
+            .to_unsigned(),
+        None
+    );
+
+    assert_eq!(
+        permissions_a
+            .get(&ResourceName::named("a"), "i64")
+            .unwrap()
+            .to_signed(),
+        Some(i64::MIN)
+    );
+
+    assert_eq!(
+        permissions_a
+            .get(&ResourceName::named("a").and("b"), "i64")
+            .unwrap()
+            .to_signed(),
+        Some(2)
+    );
+
+    assert_eq!(
+        permissions_a
+            .get(&ResourceName::named("a").and("b"), "global")
+            .unwrap()
+            .to_string(),
         "value"
     );
 
@@ -599,3 +2085,291 @@ fn configuration_tests() {
         "newvalue"
     );
 }
+
+#[test]
+fn strict_identifier_tests() {
+    let id = u64::from_be_bytes(*b"helloooo");
+    let statements = vec![
+        Statement::for_resource(ResourceName::named("users").and(id))
+            .allowing(&TestActions::Post(PostActions::Read)),
+        Statement::for_resource(ResourceName::named("users").and("helloooo"))
+            .allowing(&TestActions::Post(PostActions::Delete)),
+        Statement::for_resource(ResourceName::named("names").and(Identifier::pattern("hello*")))
+            .allowing(&TestActions::DoSomething),
+        Statement::for_resource(
+            ResourceName::named("owned").and(Identifier::variable("principal.id")),
+        )
+        .allowing(&TestActions::DoSomething),
+    ];
+    let loose = Permissions::from(statements.clone());
+    let strict = Permissions::from(statements).with_strict_identifiers();
+    assert!(!loose.uses_strict_identifiers());
+    assert!(strict.uses_strict_identifiers());
+
+    // By default, the integer and the string are the same identifier.
+    let user_id = ResourceName::named("users").and(id);
+    let user_name = ResourceName::named("users").and("helloooo");
+    for name in [&user_id, &user_name] {
+        assert!(loose.allowed_to(name, &TestActions::Post(PostActions::Read)));
+        assert!(loose.allowed_to(name, &TestActions::Post(PostActions::Delete)));
+    }
+    assert!(loose.allowed_to(
+        ResourceName::named("names").and(id),
+        &TestActions::DoSomething
+    ));
+
+    // Strict identifiers only match the same variant.
+    assert!(strict.allowed_to(&user_id, &TestActions::Post(PostActions::Read)));
+    assert!(!strict.allowed_to(&user_id, &TestActions::Post(PostActions::Delete)));
+    assert!(!strict.allowed_to(&user_name, &TestActions::Post(PostActions::Read)));
+    assert!(strict.allowed_to(&user_name, &TestActions::Post(PostActions::Delete)));
+    assert!(!strict.allowed_to(
+        ResourceName::named("users").and(b"helloooo"),
+        &TestActions::Post(PostActions::Read)
+    ));
+    assert!(strict.allowed_to(
+        ResourceName::named("names").and("hello!"),
+        &TestActions::DoSomething
+    ));
+    assert!(!strict.allowed_to(
+        ResourceName::named("names").and(id),
+        &TestActions::DoSomething
+    ));
+    let principal = Context::default().with("principal.id", id);
+    assert!(strict.allowed_to_with(
+        ResourceName::named("owned").and(id),
+        &TestActions::DoSomething,
+        &principal
+    ));
+    assert!(!strict.allowed_to_with(
+        ResourceName::named("owned").and("helloooo"),
+        &TestActions::DoSomething,
+        &principal
+    ));
+    assert!(loose.allowed_to_with(
+        ResourceName::named("owned").and("helloooo"),
+        &TestActions::DoSomething,
+        &principal
+    ));
+
+    // Each variant keeps its own resource name.
+    let resources = strict
+        .resources_allowing(&TestActions::Post(PostActions::Delete))
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(resources, ["users.helloooo"]);
+
+    // Merging with strict permissions produces strict permissions.
+    assert!(Permissions::merged(&[loose.clone(), strict.clone()]).uses_strict_identifiers());
+    assert!(!Permissions::merged(&[loose.clone(), loose]).uses_strict_identifiers());
+}
+
+#[test]
+fn strict_identifier_subset_tests() {
+    let id = u64::from_be_bytes(*b"helloooo");
+    let read = TestActions::Post(PostActions::Read);
+    let loose = Permissions::from(Statement::for_resource(id).allowing(&read));
+    let strict = loose.clone().with_strict_identifiers();
+    assert!(strict.is_subset_of(&loose));
+    assert!(loose.is_subset_of(&loose));
+
+    // Without strict identifiers, the grant also allows `"helloooo"`, which
+    // the strict permissions deny.
+    assert!(!loose.is_subset_of(&strict));
+    let difference = loose.difference(&strict);
+    assert_eq!(difference.len(), 1);
+    assert_eq!(
+        difference[0].resource.to_string(),
+        ResourceName::from(id).to_string()
+    );
+
+    // Strict permissions allowing every variant with the same bytes cover it.
+    let every_variant = Permissions::from(vec![
+        Statement::for_resource(id).allowing(&read),
+        Statement::for_resource("helloooo").allowing(&read),
+        Statement::for_resource(ResourceName::named(b"helloooo")).allowing(&read),
+        Statement::for_resource(ResourceName::named(Identifier::signed(
+            i64::try_from(id).unwrap(),
+        )))
+        .allowing(&read),
+    ])
+    .with_strict_identifiers();
+    assert!(loose.is_subset_of(&every_variant));
+    assert!(loose.is_subset_of(
+        &Permissions::from(Statement::for_any().allowing_all()).with_strict_identifiers()
+    ));
+
+    // Loose patterns match every variant, so only wildcards cover them.
+    let names = ResourceName::named("names");
+    let loose_names = Permissions::from(
+        Statement::for_resource(names.clone().and(Identifier::pattern("hello*")))
+            .allowing(&TestActions::DoSomething),
+    );
+    assert!(!loose_names.is_subset_of(&loose_names.clone().with_strict_identifiers()));
+    assert!(loose_names.is_subset_of(
+        &Permissions::from(vec![
+            Statement::for_resource(names.clone().and(Identifier::Any))
+                .allowing(&TestActions::DoSomething),
+            Statement::for_resource(ResourceName::named(b"names"))
+                .allowing(&TestActions::DoSomething),
+        ])
+        .with_strict_identifiers()
+    ));
+    assert!(!loose_names.is_subset_of(
+        &Permissions::from(
+            Statement::for_resource(names.and(Identifier::Any)).allowing(&TestActions::DoSomething),
+        )
+        .with_strict_identifiers()
+    ));
+}
+
+#[test]
+fn range_identifier_tests() {
+    let shards = ResourceName::named("shards").and(0..64);
+    let permissions = Permissions::from(vec![
+        Statement::for_resource(shards.clone())
+            .allowing(&TestActions::Post(PostActions::Read))
+            .with("replicas", 3_u64),
+        Statement::for_resource(ResourceName::named("shards").and(32..48))
+            .allowing(&TestActions::Post(PostActions::Update)),
+        Statement::for_resource(ResourceName::named("shards").and(40))
+            .denying(&TestActions::Post(PostActions::Read)),
+    ]);
+
+    let shard = |id: u64| ResourceName::named("shards").and(id);
+    assert!(permissions.allowed_to(shard(0), &TestActions::Post(PostActions::Read)));
+    assert!(permissions.allowed_to(shard(63), &TestActions::Post(PostActions::Read)));
+    assert!(!permissions.allowed_to(shard(64), &TestActions::Post(PostActions::Read)));
+    assert!(!permissions.allowed_to(shard(31), &TestActions::Post(PostActions::Update)));
+    assert!(permissions.allowed_to(shard(32), &TestActions::Post(PostActions::Update)));
+    assert!(!permissions.allowed_to(shard(48), &TestActions::Post(PostActions::Update)));
+    // An exact identifier can deny an action allowed by a range.
+    assert!(!permissions.allowed_to(shard(40), &TestActions::Post(PostActions::Read)));
+    assert!(permissions.allowed_to(shard(40), &TestActions::Post(PostActions::Update)));
+    // Ranges only match integers.
+    assert!(!permissions.allowed_to(
+        ResourceName::named("shards").and("1"),
+        &TestActions::Post(PostActions::Read)
+    ));
+
+    assert_eq!(
+        permissions
+            .get(shard(12), "replicas")
+            .and_then(Configuration::to_unsigned),
+        Some(3)
+    );
+    assert!(permissions.get(shard(64), "replicas").is_none());
+
+    // Non-negative signed integers match unless identifiers are strict.
+    let signed = ResourceName::named("shards").and(Identifier::signed(12));
+    assert!(permissions.allowed_to(&signed, &TestActions::Post(PostActions::Read)));
+    assert!(!permissions
+        .with_strict_identifiers()
+        .allowed_to(&signed, &TestActions::Post(PostActions::Read)));
+
+    assert_eq!(shards.to_string(), "shards.[0..64]");
+}
+
+#[test]
+fn document_json_tests() {
+    use crate::Document;
+
+    let statement = Statement::for_resource(ResourceName::named("users").and(Identifier::Any))
+        .denying(&"User.Delete".parse::<ActionName>().unwrap())
+        .when(Condition::All(vec![
+            Condition::greater_than_or_equal("hour", 9_u64),
+            Condition::Not(Box::new(Condition::equals("region", "eu"))),
+            Condition::Any(vec![
+                Condition::starts_with("path", "/admin"),
+                Condition::exists("override"),
+            ]),
+        ]))
+        .not_before(SystemTime::UNIX_EPOCH + Duration::from_secs(500))
+        .expiring_at(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000))
+        .with("rate-limit", 500_u64)
+        .with("offset", -1_i64)
+        .with("owner", "admin");
+
+    let json = serde_json::to_value(Document(statement.clone())).unwrap();
+    assert_eq!(json["effect"], "Deny");
+    assert_eq!(json["resources"], serde_json::json!(["users.*"]));
+    assert_eq!(json["actions"], serde_json::json!(["User.Delete"]));
+    assert_eq!(
+        json["condition"],
+        serde_json::json!({ "All": [
+            { "GreaterThanOrEqual": ["hour", 9] },
+            { "Not": { "Equals": ["region", "eu"] } },
+            { "Any": [
+                { "StartsWith": ["path", "/admin"] },
+                { "Exists": "override" }
+            ] }
+        ] })
+    );
+    assert_eq!(json["valid_from"], 500);
+    assert_eq!(json["valid_until"], 1_000);
+    assert_eq!(
+        json["configuration"],
+        serde_json::json!({ "rate-limit": 500, "offset": -1, "owner": "admin" })
+    );
+
+    let parsed = serde_json::from_value::<Document<Statement>>(json.clone())
+        .unwrap()
+        .0;
+    assert_eq!(
+        serde_json::to_value(Document(parsed.clone())).unwrap(),
+        json
+    );
+    assert_eq!(parsed.effect, Effect::Deny);
+    assert_eq!(parsed.resources[0].to_string(), "users.*");
+    assert!(matches!(
+        &parsed.actions,
+        Some(ActionNameList::List(names)) if names[0].to_string() == "User.Delete"
+    ));
+    assert_eq!(parsed.condition, statement.condition);
+    assert_eq!(parsed.valid_from, statement.valid_from);
+    assert_eq!(parsed.valid_until, statement.valid_until);
+    assert_eq!(parsed.configuration, statement.configuration);
+
+    // Fractions of a second are rounded so that the validity period doesn't
+    // grow.
+    let json = serde_json::to_value(Document(
+        Statement::allow_all_for_any_resource()
+            .not_before(SystemTime::UNIX_EPOCH + Duration::from_millis(1_500))
+            .expiring_at(SystemTime::UNIX_EPOCH + Duration::from_millis(2_500)),
+    ))
+    .unwrap();
+    assert_eq!(json["valid_from"], 2);
+    assert_eq!(json["valid_until"], 2);
+    assert!(serde_json::to_value(Document(
+        Statement::allow_all_for_any_resource()
+            .expiring_at(SystemTime::UNIX_EPOCH - Duration::from_secs(1))
+    ))
+    .is_err());
+
+    let statements = vec![statement, Statement::allow_all_for_any_resource()];
+    let json = serde_json::to_string(&Document(statements)).unwrap();
+    let parsed = serde_json::from_str::<Document<Vec<Statement>>>(&json)
+        .unwrap()
+        .0;
+    assert_eq!(parsed.len(), 2);
+    assert!(matches!(parsed[1].actions, Some(ActionNameList::All)));
+    assert_eq!(parsed[1].resources[0].to_string(), "*");
+    assert_eq!(
+        serde_json::to_value(Document(parsed)).unwrap(),
+        serde_json::from_str::<serde_json::Value>(&json).unwrap()
+    );
+
+    // Statements can be written by hand, omitting optional fields.
+    let parsed = serde_json::from_str::<Document<Statement>>(
+        r#"{ "resources": ["posts.**"], "actions": "Post.Read" }"#,
+    )
+    .unwrap()
+    .0;
+    assert_eq!(parsed.effect, Effect::Allow);
+    assert_eq!(parsed.resources[0].to_string(), "posts.**");
+    assert!(serde_json::from_str::<Document<Statement>>(
+        r#"{ "resources": ["posts"], "resource": "users" }"#
+    )
+    .is_err());
+}